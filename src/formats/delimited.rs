@@ -0,0 +1,95 @@
+use std::io::Write;
+use crate::{Heatmap, OutputError};
+
+const COLUMNS: [&str; 5] = ["date", "weekday", "week", "heat_level", "count"];
+
+/// Writes a [`Heatmap`] to the provided writer as comma-separated values, with
+/// a header row followed by one row per day.
+///
+/// # Errors
+/// - [`OutputError::Write`] fails to write to the provided writer
+///
+pub fn write_csv(heatmap: &Heatmap, writer: &mut impl Write) -> Result<(), OutputError> {
+    write_delimited(heatmap, writer, ',')
+}
+
+/// Writes a [`Heatmap`] to the provided writer as tab-separated values, with
+/// a header row followed by one row per day.
+///
+/// # Errors
+/// - [`OutputError::Write`] fails to write to the provided writer
+///
+pub fn write_tsv(heatmap: &Heatmap, writer: &mut impl Write) -> Result<(), OutputError> {
+    write_delimited(heatmap, writer, '\t')
+}
+
+fn write_delimited(heatmap: &Heatmap, writer: &mut impl Write, delimiter: char) -> Result<(), OutputError> {
+    writeln!(writer, "{}", COLUMNS.join(&delimiter.to_string())).map_err(OutputError::Write)?;
+
+    // Days missing from partial weeks fall outside of the fetched period, so they
+    // are left out rather than written as days without contributions.
+    for (week_index, week) in heatmap.contribution_weeks.iter().enumerate() {
+        for day in week.contributions.iter().flatten() {
+            writeln!(
+                writer,
+                "{date}{delimiter}{weekday}{delimiter}{week_index}{delimiter}{heat_level}{delimiter}{count}",
+                date = day.date,
+                weekday = day.date.format("%a"),
+                heat_level = day.heat_level,
+                count = day.count,
+            ).map_err(OutputError::Write)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::{Contribution, ContributionWeek};
+
+    fn heatmap() -> Heatmap {
+        let date = |day| NaiveDate::from_ymd_opt(2022, 9, day).unwrap();
+
+        Heatmap {
+            contribution_weeks: vec![
+                ContributionWeek {
+                    contributions: vec![
+                        None, None, None, None, None, None,
+                        Some(Contribution { date: date(17), heat_level: 0, count: 0 }),
+                    ]
+                },
+                ContributionWeek {
+                    contributions: vec![
+                        Some(Contribution { date: date(18), heat_level: 4, count: 12 }),
+                        None, None, None, None, None, None,
+                    ]
+                }
+            ]
+        }
+    }
+
+    #[test]
+    fn writes_csv_rows_for_available_days() {
+        let mut output = vec![];
+        write_csv(&heatmap(), &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "date,weekday,week,heat_level,count\n2022-09-17,Sat,0,0,0\n2022-09-18,Sun,1,4,12\n"
+        )
+    }
+
+    #[test]
+    fn writes_tsv_rows_for_available_days() {
+        let mut output = vec![];
+        write_tsv(&heatmap(), &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "date\tweekday\tweek\theat_level\tcount\n2022-09-17\tSat\t0\t0\t0\n2022-09-18\tSun\t1\t4\t12\n"
+        )
+    }
+}
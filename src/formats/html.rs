@@ -0,0 +1,156 @@
+use std::io::Write;
+use crate::{Heatmap, OutputError, Palette, Stats};
+use crate::heatmap::HEAT_LEVELS;
+use super::svg::render_svg;
+
+/// Writes a [`Heatmap`] to the provided writer as a self-contained HTML report,
+/// titled after the provided profile slug, on the provided RGB background color.
+///
+/// The report embeds the heatmap as an inline SVG image (see [`super::write_svg`])
+/// with hover tooltips stating the date and number of contributions of each day,
+/// followed by a legend and summary statistics.
+///
+/// # Errors
+/// - [`OutputError::Write`] fails to write to the provided writer
+///
+pub fn write_html(
+    heatmap: &Heatmap,
+    writer: &mut impl Write,
+    palette: &Palette,
+    slug: &str,
+    background: (u8, u8, u8)
+) -> Result<(), OutputError> {
+    writer.write_all(render_html(heatmap, palette, slug, background).as_bytes()).map_err(OutputError::Write)
+}
+
+fn render_html(heatmap: &Heatmap, palette: &Palette, slug: &str, background: (u8, u8, u8)) -> String {
+    let slug = escape(slug);
+    let (r, g, b) = background;
+    let (text, border) = if is_light(background) { ("#1f2328", "#d0d7de") } else { ("#c9d1d9", "#21262d") };
+    let legend: String = (0..HEAT_LEVELS)
+        .map(|level| {
+            let (r, g, b) = palette.rgb(level);
+            format!(r#"<span class="cell" style="background: rgb({r},{g},{b})"></span>"#)
+        })
+        .collect();
+
+    format!(r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Github contributions: {slug}</title>
+<style>
+  body {{ margin: 2rem; background: rgb({r},{g},{b}); color: {text}; font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; }}
+  h1 {{ font-size: 1.25rem; font-weight: 600; }}
+  .heatmap {{ overflow-x: auto; }}
+  .heatmap rect[data-date]:hover {{ stroke: {text}; }}
+  .legend {{ display: flex; align-items: center; gap: 3px; font-size: 0.75rem; color: #8b949e; margin: 0.5rem 0 1.5rem; }}
+  .legend .cell {{ display: inline-block; width: 10px; height: 10px; border-radius: 2px; }}
+  table {{ border-collapse: collapse; }}
+  th, td {{ text-align: left; padding: 0.25rem 1.5rem 0.25rem 0; border-bottom: 1px solid {border}; }}
+  th {{ color: #8b949e; font-weight: 400; }}
+</style>
+</head>
+<body>
+<h1>Github contributions: {slug}</h1>
+<div class="heatmap">
+{svg}</div>
+<div class="legend"><span>Less</span>{legend}<span>More</span></div>
+<table>
+{summary}</table>
+</body>
+</html>
+"#,
+        svg = render_svg(heatmap, palette, background),
+        summary = render_summary(heatmap),
+    )
+}
+
+fn render_summary(heatmap: &Heatmap) -> String {
+    Stats::from_heatmap(heatmap)
+        .summary()
+        .iter()
+        .map(|(label, value)| format!("<tr><th>{label}</th><td>{}</td></tr>\n", escape(value)))
+        .collect()
+}
+
+/// Whether text should be drawn dark on the provided RGB background color,
+/// judged by its relative luminance.
+fn is_light((r, g, b): (u8, u8, u8)) -> bool {
+    299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b) > 128_000
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::{ColorValues, Contribution, ContributionWeek};
+
+    fn heatmap() -> Heatmap {
+        let date = |day| NaiveDate::from_ymd_opt(2022, 9, day).unwrap();
+
+        Heatmap {
+            contribution_weeks: vec![
+                ContributionWeek {
+                    contributions: vec![
+                        None, None, None, None,
+                        Some(Contribution { date: date(15), heat_level: 2, count: 4 }),
+                        Some(Contribution { date: date(16), heat_level: 0, count: 0 }),
+                        Some(Contribution { date: date(17), heat_level: 4, count: 9 }),
+                    ]
+                }
+            ]
+        }
+    }
+
+    #[test]
+    fn renders_summary_statistics() {
+        let summary = render_summary(&heatmap());
+
+        assert!(summary.contains("<tr><th>Period</th><td>September 15, 2022 – September 17, 2022</td></tr>"));
+        assert!(summary.contains("<tr><th>Total contributions</th><td>13</td></tr>"));
+        assert!(summary.contains("<tr><th>Active days</th><td>2 of 3</td></tr>"));
+        assert!(summary.contains("<tr><th>Busiest day</th><td>September 17, 2022 (9)</td></tr>"));
+        assert!(summary.contains("<tr><th>Longest streak</th><td>1 day, September 15, 2022 – September 15, 2022</td></tr>"));
+    }
+
+    #[test]
+    fn renders_self_contained_report() {
+        let html = render_html(&heatmap(), &Palette::from(&ColorValues::Green), "torvalds", (0x0d, 0x11, 0x17));
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Github contributions: torvalds</title>"));
+        assert!(html.contains("<title>9 contributions on Saturday, September 17, 2022</title>"));
+        assert!(html.contains(r#"<span>Less</span><span class="cell" style="background: rgb(0,0,0)"></span>"#));
+        assert!(!html.contains("<script") && !html.contains("<link"));
+    }
+
+    #[test]
+    fn styles_report_after_background() {
+        let light = render_html(&heatmap(), &Palette::from(&ColorValues::Green), "torvalds", (255, 255, 255));
+        let dark = render_html(&heatmap(), &Palette::from(&ColorValues::Green), "torvalds", (0x0d, 0x11, 0x17));
+
+        assert!(light.contains("background: rgb(255,255,255); color: #1f2328;"));
+        assert!(light.contains(r#"<rect width="53" height="121" fill="rgb(255,255,255)" />"#));
+        assert!(dark.contains("background: rgb(13,17,23); color: #c9d1d9;"));
+    }
+
+    #[test]
+    fn detects_light_backgrounds() {
+        assert!(is_light((255, 255, 255)));
+        assert!(!is_light((0x0d, 0x11, 0x17)));
+    }
+
+    #[test]
+    fn escapes_markup() {
+        assert_eq!(escape(r#"<a href="x">&</a>"#), "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;");
+    }
+}
@@ -0,0 +1,74 @@
+use std::io::Write;
+use crate::{Heatmap, OutputError};
+
+/// Writes a [`Heatmap`] to the provided writer as pretty-printed JSON.
+///
+/// The schema mirrors the `Heatmap`, [`crate::ContributionWeek`] and
+/// [`crate::Contribution`] structs, with dates formatted as `YYYY-MM-DD`:
+///
+/// ```json
+/// { "contribution_weeks": [ { "contributions": [ null, { "date": "2022-09-19", "heat_level": 2, "count": 5 } ] } ] }
+/// ```
+///
+/// # Errors
+/// - [`OutputError::Unsupported`] crate was built without the `serde` feature
+/// - [`OutputError::Write`] fails to write to the provided writer
+///
+#[cfg(feature = "serde")]
+pub fn write_json(heatmap: &Heatmap, writer: &mut impl Write) -> Result<(), OutputError> {
+    serde_json::to_writer_pretty(&mut *writer, heatmap).map_err(|e| OutputError::Write(e.into()))?;
+    writeln!(writer).map_err(OutputError::Write)
+}
+
+/// Writes a [`Heatmap`] to the provided writer as pretty-printed JSON.
+///
+/// # Errors
+/// - [`OutputError::Unsupported`] crate was built without the `serde` feature
+///
+#[cfg(not(feature = "serde"))]
+pub fn write_json(_heatmap: &Heatmap, _writer: &mut impl Write) -> Result<(), OutputError> {
+    Err(OutputError::Unsupported("json output requires the 'serde' feature".to_string()))
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use serde_json::{json, Value};
+    use crate::{Contribution, ContributionWeek};
+
+    fn heatmap() -> Heatmap {
+        Heatmap {
+            contribution_weeks: vec![
+                ContributionWeek {
+                    contributions: vec![
+                        None,
+                        Some(Contribution { date: NaiveDate::from_ymd_opt(2022, 9, 19).unwrap(), heat_level: 2, count: 5 }),
+                    ]
+                }
+            ]
+        }
+    }
+
+    #[test]
+    fn writes_documented_schema() {
+        let mut output = vec![];
+        write_json(&heatmap(), &mut output).unwrap();
+        let value: Value = serde_json::from_slice(&output).unwrap();
+
+        assert_eq!(value, json!({
+            "contribution_weeks": [
+                { "contributions": [null, { "date": "2022-09-19", "heat_level": 2, "count": 5 }] }
+            ]
+        }))
+    }
+
+    #[test]
+    fn deserializes_written_heatmap() {
+        let mut output = vec![];
+        write_json(&heatmap(), &mut output).unwrap();
+        let parsed: Heatmap = serde_json::from_slice(&output).unwrap();
+
+        assert_eq!(parsed, heatmap())
+    }
+}
@@ -0,0 +1,11 @@
+mod delimited;
+mod html;
+mod json;
+mod png;
+mod svg;
+
+pub use delimited::{write_csv, write_tsv};
+pub use html::write_html;
+pub use json::write_json;
+pub use self::png::{write_png, PngOptions};
+pub use svg::write_svg;
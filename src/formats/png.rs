@@ -0,0 +1,204 @@
+use std::io::Write;
+use png::{BitDepth, ColorType, Encoder};
+use crate::{Heatmap, OutputError, Palette};
+use crate::heatmap::DAYS_IN_WEEK;
+
+const CHANNELS: usize = 3;
+const MAX_DIMENSION: u32 = 1 << 15;
+const MAX_PIXELS: u64 = 1 << 26;
+
+/// Layout options used while rasterizing a [`Heatmap`] with [`write_png`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PngOptions {
+    /// Width and height of each heatmap node, in pixels.
+    pub cell_size: u32,
+    /// Space between two adjacent heatmap nodes, in pixels.
+    pub gap: u32,
+    /// Space between the heatmap nodes and the edges of the image, in pixels.
+    pub padding: u32,
+    /// RGB color of the image background.
+    pub background: (u8, u8, u8),
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        PngOptions {
+            cell_size: 10,
+            gap: 3,
+            padding: 10,
+            background: (0x0d, 0x11, 0x17),
+        }
+    }
+}
+
+/// Writes a [`Heatmap`] to the provided writer as a PNG image, rasterized
+/// without relying on any system libraries.
+///
+/// Nodes are drawn as rounded cells shaded with the provided [`Palette`],
+/// laid out according to the provided [`PngOptions`].
+///
+/// # Errors
+/// - [`OutputError::ImageDimensions`] layout options produce an empty image, one
+///   wider or taller than 32768 pixels, or one of more than 2^26 pixels in total
+/// - [`OutputError::Write`] fails to encode the image or write it to the provided writer
+///
+pub fn write_png(
+    heatmap: &Heatmap,
+    writer: &mut impl Write,
+    palette: &Palette,
+    options: &PngOptions
+) -> Result<(), OutputError> {
+    let (width, height, pixels) = rasterize(heatmap, palette, options)?;
+
+    let mut encoder = Encoder::new(writer, width, height);
+    encoder.set_color(ColorType::Rgb);
+    encoder.set_depth(BitDepth::Eight);
+
+    let mut png_writer = encoder.write_header().map_err(|e| OutputError::Write(e.into()))?;
+    png_writer.write_image_data(&pixels).map_err(|e| OutputError::Write(e.into()))?;
+    png_writer.finish().map_err(|e| OutputError::Write(e.into()))
+}
+
+fn rasterize(heatmap: &Heatmap, palette: &Palette, options: &PngOptions) -> Result<(u32, u32, Vec<u8>), OutputError> {
+    let (width, height) = dimensions(heatmap.contribution_weeks.len(), options)?;
+    let step = options.cell_size + options.gap;
+
+    let (r, g, b) = options.background;
+    let mut pixels = [r, g, b].repeat((width * height) as usize);
+
+    for (week_index, week) in heatmap.contribution_weeks.iter().enumerate() {
+        for (day_index, day) in week.contributions.iter().enumerate() {
+            if let Some(day) = day {
+                let x = options.padding + week_index as u32 * step;
+                let y = options.padding + day_index as u32 * step;
+                fill_cell(&mut pixels, width, x, y, options.cell_size, palette.rgb(day.heat_level));
+            }
+        }
+    }
+
+    Ok((width, height, pixels))
+}
+
+/// Computes the width and height of the image, guarding against empty images,
+/// arithmetic overflow and oversized allocations caused by extreme layout options.
+fn dimensions(weeks: usize, options: &PngOptions) -> Result<(u32, u32), OutputError> {
+    let too_large = || OutputError::ImageDimensions(format!("images are limited to {MAX_DIMENSION} pixels per side"));
+
+    if options.cell_size == 0 {
+        return Err(OutputError::ImageDimensions("cell size must be at least 1 pixel".to_string()));
+    }
+
+    let step = options.cell_size.checked_add(options.gap).ok_or_else(too_large)?;
+    let padding = options.padding.checked_mul(2).ok_or_else(too_large)?;
+    let side = |cells: u32| cells
+        .checked_mul(step)
+        .map(|length| length.saturating_sub(options.gap).max(1))
+        .and_then(|length| length.checked_add(padding))
+        .filter(|length| *length <= MAX_DIMENSION)
+        .ok_or_else(too_large);
+
+    let weeks = u32::try_from(weeks).map_err(|_| too_large())?;
+
+    let (width, height) = (side(weeks)?, side(DAYS_IN_WEEK as u32)?);
+
+    if u64::from(width) * u64::from(height) > MAX_PIXELS {
+        return Err(OutputError::ImageDimensions(format!("images are limited to {MAX_PIXELS} pixels in total")));
+    }
+
+    Ok((width, height))
+}
+
+fn fill_cell(pixels: &mut [u8], width: u32, x: u32, y: u32, size: u32, (r, g, b): (u8, u8, u8)) {
+    // Corners are rounded by skipping pixels outside of a quarter circle in each
+    // corner, similar to the rx/ry attributes of the SVG cells.
+    let radius = size / 5;
+
+    for dy in 0..size {
+        for dx in 0..size {
+            let corner_x = radius.saturating_sub(dx).max((dx + radius + 1).saturating_sub(size));
+            let corner_y = radius.saturating_sub(dy).max((dy + radius + 1).saturating_sub(size));
+
+            if corner_x * corner_x + corner_y * corner_y > radius * radius {
+                continue;
+            }
+
+            let offset = (((y + dy) * width + x + dx) as usize) * CHANNELS;
+            pixels[offset..offset + CHANNELS].copy_from_slice(&[r, g, b]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::{ColorValues, Contribution, ContributionWeek};
+
+    fn heatmap() -> Heatmap {
+        let date = NaiveDate::from_ymd_opt(2022, 9, 18).unwrap();
+
+        Heatmap {
+            contribution_weeks: vec![
+                ContributionWeek {
+                    contributions: vec![
+                        Some(Contribution { date, heat_level: 4, count: 12 }),
+                        None, None, None, None, None, None,
+                    ]
+                },
+                ContributionWeek { contributions: vec![None; 7] }
+            ]
+        }
+    }
+
+    fn pixel(pixels: &[u8], width: u32, x: u32, y: u32) -> (u8, u8, u8) {
+        let offset = ((y * width + x) as usize) * CHANNELS;
+        (pixels[offset], pixels[offset + 1], pixels[offset + 2])
+    }
+
+    #[test]
+    fn rasterizes_cells_with_layout_options() {
+        let options = PngOptions { cell_size: 10, gap: 2, padding: 4, background: (255, 255, 255) };
+        let (width, height, pixels) = rasterize(&heatmap(), &Palette::from(&ColorValues::Red), &options).unwrap();
+
+        assert_eq!((width, height), (30, 90));
+        assert_eq!(pixel(&pixels, width, 9, 9), (255, 0, 0));
+        assert_eq!(pixel(&pixels, width, 0, 0), (255, 255, 255));
+        // Rounded corner of the cell, and the empty day below it.
+        assert_eq!(pixel(&pixels, width, 4, 4), (255, 255, 255));
+        assert_eq!(pixel(&pixels, width, 9, 21), (255, 255, 255));
+    }
+
+    #[test]
+    fn error_if_image_empty_or_too_large() {
+        let palette = Palette::from(&ColorValues::Green);
+        let empty = PngOptions { cell_size: 0, gap: 0, padding: 0, ..PngOptions::default() };
+        let overflowing = PngOptions { cell_size: u32::MAX, gap: u32::MAX, padding: u32::MAX, ..PngOptions::default() };
+        let too_large = PngOptions { cell_size: 5000, ..PngOptions::default() };
+
+        for options in [empty, overflowing, too_large] {
+            let error = write_png(&heatmap(), &mut vec![], &palette, &options).unwrap_err();
+            assert!(matches!(error, OutputError::ImageDimensions(_)), "{options:?}");
+        }
+    }
+
+    #[test]
+    fn error_if_image_has_too_many_pixels() {
+        // Both sides fit within the limit, but the image would take up about 3 GB.
+        let options = PngOptions { padding: 16000, ..PngOptions::default() };
+        let error = write_png(&heatmap(), &mut vec![], &Palette::from(&ColorValues::Green), &options).unwrap_err();
+
+        assert!(matches!(error, OutputError::ImageDimensions(message) if message.contains("in total")));
+    }
+
+    #[test]
+    fn writes_png_image() {
+        let mut output = vec![];
+        write_png(&heatmap(), &mut output, &Palette::from(&ColorValues::Green), &PngOptions::default()).unwrap();
+
+        let decoder = png::Decoder::new(output.as_slice());
+        let reader = decoder.read_info().unwrap();
+
+        assert_eq!(reader.info().width, 2 * 10 + 2 * 13 - 3);
+        assert_eq!(reader.info().height, 2 * 10 + 7 * 13 - 3);
+    }
+}
@@ -0,0 +1,162 @@
+use std::{fmt::Write as _, io::Write};
+use crate::{Heatmap, OutputError, Palette};
+use crate::heatmap::DAYS_IN_WEEK;
+
+const CELL_SIZE: usize = 10;
+const CELL_STEP: usize = 13;
+const CELL_RADIUS: usize = 2;
+const LEFT_MARGIN: usize = 30;
+const TOP_MARGIN: usize = 20;
+const PADDING: usize = 10;
+const LABEL_COLOR: &str = "#8b949e";
+const MONTH_LABEL_WIDTH: usize = 20;
+const WEEKDAY_LABELS: [(usize, &str); 3] = [(1, "Mon"), (3, "Wed"), (5, "Fri")];
+
+/// Writes a [`Heatmap`] to the provided writer as a standalone SVG image,
+/// mirroring the layout of the contribution calendar on a Github profile page.
+///
+/// Nodes are drawn as rounded cells shaded with the provided [`Palette`] on the
+/// provided RGB background color, along with month and weekday labels. Each cell
+/// contains a `<title>` element, shown as a tooltip by most SVG viewers.
+///
+/// # Errors
+/// - [`OutputError::Write`] fails to write to the provided writer
+///
+pub fn write_svg(
+    heatmap: &Heatmap,
+    writer: &mut impl Write,
+    palette: &Palette,
+    background: (u8, u8, u8)
+) -> Result<(), OutputError> {
+    writer.write_all(render_svg(heatmap, palette, background).as_bytes()).map_err(OutputError::Write)
+}
+
+/// Generates the markup of an SVG image of the provided [`Heatmap`], without the
+/// surrounding XML declaration. See [`write_svg`].
+pub(crate) fn render_svg(heatmap: &Heatmap, palette: &Palette, background: (u8, u8, u8)) -> String {
+    let (r, g, b) = background;
+    let width = LEFT_MARGIN + heatmap.contribution_weeks.len() * CELL_STEP + PADDING;
+    let height = TOP_MARGIN + DAYS_IN_WEEK * CELL_STEP + PADDING;
+    let mut svg = String::new();
+
+    // Writing to a String cannot fail, so results are ignored.
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="-apple-system, BlinkMacSystemFont, 'Segoe UI', Helvetica, Arial, sans-serif" font-size="9">"#
+    );
+    let _ = writeln!(svg, r#"  <rect width="{width}" height="{height}" fill="rgb({r},{g},{b})" />"#);
+
+    // Like the terminal grid, labels overlapping the previous label are skipped.
+    let mut labels_end = 0;
+
+    for (week_index, date) in heatmap.month_starts() {
+        let x = LEFT_MARGIN + week_index * CELL_STEP;
+
+        if x >= labels_end {
+            let _ = writeln!(svg, r#"  <text x="{x}" y="{}" fill="{LABEL_COLOR}">{}</text>"#, TOP_MARGIN - 7, date.format("%b"));
+            labels_end = x + MONTH_LABEL_WIDTH;
+        }
+    }
+
+    for (day_index, label) in WEEKDAY_LABELS {
+        let y = TOP_MARGIN + day_index * CELL_STEP + CELL_SIZE - 1;
+        let _ = writeln!(svg, r#"  <text x="{PADDING}" y="{y}" fill="{LABEL_COLOR}">{label}</text>"#);
+    }
+
+    for (week_index, week) in heatmap.contribution_weeks.iter().enumerate() {
+        for (day_index, day) in week.contributions.iter().enumerate() {
+            if let Some(day) = day {
+                let (r, g, b) = palette.rgb(day.heat_level);
+                let _ = writeln!(
+                    svg,
+                    r#"  <rect x="{x}" y="{y}" width="{CELL_SIZE}" height="{CELL_SIZE}" rx="{CELL_RADIUS}" ry="{CELL_RADIUS}" fill="rgb({r},{g},{b})" stroke="rgba(255,255,255,0.05)" data-date="{date}" data-level="{level}" data-count="{count}"><title>{title}</title></rect>"#,
+                    x = LEFT_MARGIN + week_index * CELL_STEP,
+                    y = TOP_MARGIN + day_index * CELL_STEP,
+                    date = day.date,
+                    level = day.heat_level,
+                    count = day.count,
+                    title = day.tooltip(),
+                );
+            }
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::{ColorValues, Contribution, ContributionWeek};
+
+    const BACKGROUND: (u8, u8, u8) = (0x0d, 0x11, 0x17);
+
+    fn heatmap() -> Heatmap {
+        let date = |month, day| NaiveDate::from_ymd_opt(2022, month, day).unwrap();
+
+        Heatmap {
+            contribution_weeks: vec![
+                ContributionWeek {
+                    contributions: vec![
+                        None, None, None, None, None, None,
+                        Some(Contribution { date: date(9, 17), heat_level: 0, count: 0 }),
+                    ]
+                },
+                ContributionWeek {
+                    contributions: vec![
+                        Some(Contribution { date: date(10, 2), heat_level: 4, count: 12 }),
+                        None, None, None, None, None, None,
+                    ]
+                }
+            ]
+        }
+    }
+
+    #[test]
+    fn renders_cells_with_tooltips() {
+        let svg = render_svg(&heatmap(), &Palette::from(&ColorValues::Blue), BACKGROUND);
+
+        assert!(svg.contains(
+            r#"<rect x="43" y="20" width="10" height="10" rx="2" ry="2" fill="rgb(0,0,255)" stroke="rgba(255,255,255,0.05)" data-date="2022-10-02" data-level="4" data-count="12"><title>12 contributions on Sunday, October 2, 2022</title></rect>"#
+        ));
+        assert!(svg.contains("<title>No contributions on Saturday, September 17, 2022</title>"));
+        assert_eq!(svg.matches("<title>").count(), 2);
+    }
+
+    #[test]
+    fn renders_month_and_weekday_labels() {
+        let svg = render_svg(&heatmap(), &Palette::from(&ColorValues::Green), BACKGROUND);
+
+        assert!(svg.contains(r##"<text x="30" y="13" fill="#8b949e">Sep</text>"##));
+        assert!(!svg.contains(">Oct</text>"));
+        assert!(svg.contains(">Mon</text>") && svg.contains(">Wed</text>") && svg.contains(">Fri</text>"));
+    }
+
+    #[test]
+    fn labels_months_two_weeks_apart() {
+        let date = |month, day| NaiveDate::from_ymd_opt(2022, month, day).unwrap();
+        let heatmap = Heatmap::from_contributions(vec![
+            Contribution { date: date(9, 17), heat_level: 0, count: 0 },
+            Contribution { date: date(9, 24), heat_level: 0, count: 0 },
+            Contribution { date: date(10, 1), heat_level: 0, count: 0 },
+        ]);
+        let svg = render_svg(&heatmap, &Palette::from(&ColorValues::Green), BACKGROUND);
+
+        assert!(svg.contains(r##"<text x="30" y="13" fill="#8b949e">Sep</text>"##));
+        assert!(svg.contains(r##"<text x="56" y="13" fill="#8b949e">Oct</text>"##));
+    }
+
+    #[test]
+    fn sizes_image_to_fit_weeks() {
+        let svg = render_svg(&heatmap(), &Palette::from(&ColorValues::Green), BACKGROUND);
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="66" height="121""#));
+    }
+
+    #[test]
+    fn fills_provided_background() {
+        let svg = render_svg(&heatmap(), &Palette::from(&ColorValues::Green), (255, 255, 255));
+        assert!(svg.contains(r#"<rect width="66" height="121" fill="rgb(255,255,255)" />"#));
+    }
+}
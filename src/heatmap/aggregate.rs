@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+use chrono::NaiveDate;
+use super::{Contribution, Heatmap};
+
+const QUARTILES: [usize; 3] = [1, 2, 3];
+
+impl Heatmap {
+    /// Combines several heatmaps, e.g. of the members of a team, into a single
+    /// `Heatmap` whose daily counts are the sum of the counts of every heatmap
+    /// on that day.
+    ///
+    /// Heat levels of the combined heatmap are recomputed from its summed counts,
+    /// see [`Heatmap::rebucket`].
+    ///
+    pub fn sum(heatmaps: &[Heatmap]) -> Self {
+        let mut totals: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+
+        for contribution in heatmaps.iter().flat_map(Heatmap::days) {
+            *totals.entry(contribution.date).or_default() += contribution.count;
+        }
+
+        let contributions = totals
+            .into_iter()
+            .map(|(date, count)| Contribution { date, heat_level: 0, count })
+            .collect();
+
+        let mut heatmap = Heatmap::from_contributions(contributions);
+        heatmap.rebucket();
+        heatmap
+    }
+
+    /// Recomputes the heat level of every [`Contribution`] from the quartiles of
+    /// the non-zero counts of the `Heatmap`, in the same way as Github.
+    ///
+    /// Days without contributions are assigned level 0, while the remaining days
+    /// are split evenly across levels 1 to 4.
+    ///
+    pub fn rebucket(&mut self) {
+        let thresholds = quartiles(self.days().map(|contribution| contribution.count));
+
+        for contribution in self.days_mut() {
+            contribution.heat_level = quartile_level(contribution.count, &thresholds);
+        }
+    }
+}
+
+/// Computes the nearest-rank quartiles of the non-zero values.
+pub(super) fn quartiles(values: impl Iterator<Item = usize>) -> [usize; 3] {
+    let mut values: Vec<usize> = values.filter(|value| *value > 0).collect();
+    values.sort_unstable();
+
+    QUARTILES.map(|quartile| match values.len() {
+        0 => 0,
+        len => values[(len * quartile).div_ceil(4).max(1) - 1],
+    })
+}
+
+/// Assigns level 0 to zero, and levels 1 to 4 to non-zero values depending on
+/// the quartiles computed by [`quartiles`].
+pub(super) fn quartile_level(value: usize, thresholds: &[usize; 3]) -> usize {
+    match value {
+        0 => 0,
+        value => 1 + thresholds.iter().filter(|threshold| value > **threshold).count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn days(heatmap: &Heatmap) -> Vec<(String, usize, usize)> {
+        heatmap.days()
+            .map(|day| (day.date.to_string(), day.count, day.heat_level))
+            .collect()
+    }
+
+    #[test]
+    fn sums_daily_counts() {
+        let contribution = |value, count| Contribution { date: date(value), heat_level: 4, count };
+        let alice = Heatmap::from_contributions(vec![contribution("2022-09-17", 2), contribution("2022-09-18", 0)]);
+        let bob = Heatmap::from_contributions(vec![contribution("2022-09-18", 3), contribution("2022-09-19", 1)]);
+
+        let team = Heatmap::sum(&[alice, bob]);
+
+        assert_eq!(days(&team), vec![
+            ("2022-09-17".to_string(), 2, 2),
+            ("2022-09-18".to_string(), 3, 3),
+            ("2022-09-19".to_string(), 1, 1),
+        ]);
+        assert_eq!(team.contribution_weeks.len(), 2);
+    }
+
+    #[test]
+    fn rebuckets_heat_levels_by_quartiles() {
+        let start = date("2022-09-18");
+        let mut heatmap = Heatmap::from_contributions(
+            (0..9)
+                .map(|offset| Contribution { date: start + chrono::Duration::days(offset), heat_level: 0, count: offset as usize })
+                .collect()
+        );
+
+        heatmap.rebucket();
+        let levels: Vec<_> = days(&heatmap).into_iter().map(|(_, _, level)| level).collect();
+
+        assert_eq!(levels, vec![0, 1, 1, 2, 2, 3, 3, 4, 4]);
+    }
+}
@@ -0,0 +1,193 @@
+use std::env;
+use colored::{Color, Colorize};
+use crate::ColorModeValues;
+use super::GlyphRamp;
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+const ANSI_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::White, (229, 229, 229)),
+    (Color::BrightBlack, (127, 127, 127)),
+    (Color::BrightRed, (255, 0, 0)),
+    (Color::BrightGreen, (0, 255, 0)),
+    (Color::BrightYellow, (255, 255, 0)),
+    (Color::BrightBlue, (92, 92, 255)),
+    (Color::BrightMagenta, (255, 0, 255)),
+    (Color::BrightCyan, (0, 255, 255)),
+    (Color::BrightWhite, (255, 255, 255)),
+];
+
+/// The range of colors supported by the terminal that a heatmap is rendered to.
+///
+/// Palette colors are quantized to the nearest supported color, or replaced
+/// by the glyphs of the default [`GlyphRamp`] if the terminal does not support color.
+///
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum ColorDepth {
+    /// 24-bit RGB colors.
+    #[default]
+    TrueColor,
+    /// The 256 color xterm palette.
+    Ansi256,
+    /// The 16 standard ANSI colors.
+    Ansi16,
+    /// No color, heat levels are shaded with glyphs instead.
+    Monochrome,
+}
+
+impl ColorDepth {
+    /// Detects the color depth supported by the current terminal from the
+    /// `NO_COLOR`, `COLORTERM` and `TERM` environment variables. Falls back to
+    /// [`ColorDepth::Monochrome`] if `colored` will not colorize standard output,
+    /// e.g. when it is piped or redirected.
+    pub fn detect() -> Self {
+        Self::from_env(colored::control::SHOULD_COLORIZE.should_colorize(), |name| env::var(name).ok())
+    }
+
+    fn from_env(colorize: bool, var: impl Fn(&str) -> Option<String>) -> Self {
+        if !colorize || var("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return ColorDepth::Monochrome;
+        }
+
+        if let Some("truecolor" | "24bit") = var("COLORTERM").as_deref() {
+            return ColorDepth::TrueColor;
+        }
+
+        match var("TERM").as_deref() {
+            None | Some("" | "dumb") => ColorDepth::Monochrome,
+            Some(term) if term.contains("256color") => ColorDepth::Ansi256,
+            Some(_) => ColorDepth::Ansi16,
+        }
+    }
+
+    /// Paints the provided text with the nearest supported color to the provided
+    /// RGB color. Text is painted as a glyph corresponding to the heat level in
+    /// [`ColorDepth::Monochrome`] mode instead.
+    pub fn paint(self, text: &str, rgb: (u8, u8, u8), heat_level: usize) -> String {
+        let (r, g, b) = rgb;
+
+        match self {
+            ColorDepth::TrueColor => text.color(Color::TrueColor { r, g, b }).to_string(),
+            ColorDepth::Ansi256 if colored::control::SHOULD_COLORIZE.should_colorize() => {
+                format!("\x1b[38;5;{}m{text}\x1b[0m", xterm_256(rgb))
+            },
+            ColorDepth::Ansi256 => text.to_string(),
+            ColorDepth::Ansi16 => text.color(ansi_16(rgb)).to_string(),
+            ColorDepth::Monochrome => {
+                let glyph = GlyphRamp::default().glyph(heat_level);
+                text.replacen('\u{025A0}', &glyph.to_string(), 1)
+            },
+        }
+    }
+}
+
+impl From<&ColorModeValues> for ColorDepth {
+    /// Detects the color depth of the terminal for [`ColorModeValues::Auto`].
+    fn from(mode: &ColorModeValues) -> Self {
+        match mode {
+            ColorModeValues::Auto => ColorDepth::detect(),
+            ColorModeValues::Truecolor => ColorDepth::TrueColor,
+            ColorModeValues::Ansi256 => ColorDepth::Ansi256,
+            ColorModeValues::Ansi16 => ColorDepth::Ansi16,
+            ColorModeValues::None => ColorDepth::Monochrome,
+        }
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let channel = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    channel(r1, r2) + channel(g1, g2) + channel(b1, b2)
+}
+
+/// Quantizes an RGB color to the nearest color of the 6x6x6 color cube or the
+/// grayscale ramp of the xterm 256 color palette.
+fn xterm_256(rgb: (u8, u8, u8)) -> u8 {
+    let nearest_level = |channel: u8| (0..CUBE_LEVELS.len())
+        .min_by_key(|index| (CUBE_LEVELS[*index] as i32 - channel as i32).abs())
+        .unwrap();
+
+    let (r, g, b) = (nearest_level(rgb.0), nearest_level(rgb.1), nearest_level(rgb.2));
+    let cube_index = 16 + 36 * r + 6 * g + b;
+    let cube_rgb = (CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+
+    let average = (rgb.0 as usize + rgb.1 as usize + rgb.2 as usize) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23);
+    let gray_level = (8 + gray_step * 10) as u8;
+
+    if distance(rgb, (gray_level, gray_level, gray_level)) < distance(rgb, cube_rgb) {
+        (232 + gray_step) as u8
+    } else {
+        cube_index as u8
+    }
+}
+
+/// Quantizes an RGB color to the nearest of the 16 standard ANSI colors.
+fn ansi_16(rgb: (u8, u8, u8)) -> Color {
+    ANSI_COLORS
+        .iter()
+        .min_by_key(|(_, ansi_rgb)| distance(rgb, *ansi_rgb))
+        .map(|(color, _)| *color)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(vars: &[(&str, &str)]) -> ColorDepth {
+        ColorDepth::from_env(true, |name| vars
+            .iter()
+            .find(|(var, _)| *var == name)
+            .map(|(_, value)| value.to_string()))
+    }
+
+    #[test]
+    fn detects_color_depth_from_environment() {
+        assert_eq!(detect(&[("COLORTERM", "truecolor"), ("TERM", "xterm-256color")]), ColorDepth::TrueColor);
+        assert_eq!(detect(&[("TERM", "tmux-256color")]), ColorDepth::Ansi256);
+        assert_eq!(detect(&[("TERM", "linux")]), ColorDepth::Ansi16);
+        assert_eq!(detect(&[("TERM", "dumb")]), ColorDepth::Monochrome);
+        assert_eq!(detect(&[]), ColorDepth::Monochrome);
+    }
+
+    #[test]
+    fn no_color_takes_precedence() {
+        assert_eq!(detect(&[("NO_COLOR", "1"), ("COLORTERM", "truecolor")]), ColorDepth::Monochrome);
+        assert_eq!(detect(&[("NO_COLOR", ""), ("COLORTERM", "truecolor")]), ColorDepth::TrueColor);
+    }
+
+    #[test]
+    fn falls_back_to_monochrome_without_colorized_output() {
+        let depth = ColorDepth::from_env(false, |name| (name == "COLORTERM").then(|| "truecolor".to_string()));
+        assert_eq!(depth, ColorDepth::Monochrome);
+    }
+
+    #[test]
+    fn quantizes_to_xterm_256() {
+        assert_eq!(xterm_256((0, 0, 0)), 16);
+        assert_eq!(xterm_256((255, 255, 255)), 231);
+        assert_eq!(xterm_256((0x21, 0x6e, 0x39)), 23);
+        assert_eq!(xterm_256((0xeb, 0xed, 0xf0)), 255);
+    }
+
+    #[test]
+    fn quantizes_to_ansi_16() {
+        assert_eq!(ansi_16((0, 64, 0)), Color::Black);
+        assert_eq!(ansi_16((0, 127, 0)), Color::Green);
+        assert_eq!(ansi_16((0, 255, 0)), Color::BrightGreen);
+        assert_eq!(ansi_16((0x39, 0xd3, 0x53)), Color::Green);
+        assert_eq!(ansi_16((0xfd, 0xe7, 0x25)), Color::BrightYellow);
+    }
+
+    #[test]
+    fn paints_glyphs_in_monochrome() {
+        assert_eq!(ColorDepth::Monochrome.paint("\u{025A0} ", (0, 0, 0), 0), "· ");
+        assert_eq!(ColorDepth::Monochrome.paint("\u{025A0} ", (0, 255, 0), 4), "█ ");
+    }
+}
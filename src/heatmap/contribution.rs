@@ -1,25 +1,40 @@
-use colored::{Color, Colorize};
+use chrono::NaiveDate;
 use scraper::ElementRef;
-use crate::{ColorValues, HeatmapError};
+use crate::HeatmapError;
+use super::Palette;
 
 const LEVEL_ATTR: &str = "data-level";
+const DATE_ATTR: &str = "data-date";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TOOLTIP_ELEMENTS: [&str; 2] = ["tool-tip", "span"];
 
 /// A `Contribution` instance represents an invidividual heatmap node, with
-/// a heat level corresponding to the data-level attribute set on the scraped
-/// SVG Rect element.
+/// a calendar date and heat level corresponding to the data-date and data-level
+/// attributes set on the scraped SVG Rect element, and an exact contribution count
+/// parsed from the node's tooltip text.
 ///
 /// `Contribution` instances are typically not constructed explicitly, rather created
 /// implicitly by the higher level `Heatmap` struct via the `from_el` associated method.
 ///
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Contribution {
+    /// The `date` property corresponds to the Rect element's data-date attribute,
+    /// which identifies the calendar day that the contributions were made on.
+    pub date: NaiveDate,
+
     /// The `heat_level` property corresponds to the Rect element's data-level attribute,
     /// which Github uses to determine the intensity when shading the Rect element on
     /// the front end. 
     ///
     /// The `heat_level` property is utilised in the same way when deciding
     /// on the intensity of the filled Unicode box character.
-    pub heat_level: usize
+    pub heat_level: usize,
+
+    /// The `count` property corresponds to the exact number of contributions made
+    /// on the day, as stated by the tooltip text accompanying the Rect element
+    /// (e.g. "4 contributions on Sunday, September 18, 2022").
+    pub count: usize,
 }
 
 impl Contribution {
@@ -28,36 +43,89 @@ impl Contribution {
     /// node.
     ///
     /// # Errors
-    /// - [`HeatmapError::QueryAttribute`] fails to query heat level attribute
-    /// - [`HeatmapError::ParseAttribute`] fails to parse heat level attribute
+    /// - [`HeatmapError::QueryAttribute`] fails to query date or heat level attribute
+    /// - [`HeatmapError::ParseAttribute`] fails to parse date or heat level attribute
+    /// - [`HeatmapError::ParseContributionCount`] fails to parse count from tooltip text
     ///
     pub fn from_el(el: &ElementRef) -> Result<Self, HeatmapError> {
+       let date = Self::parse_date(el)?;
        let heat_level = Self::parse_heat_level(el)?;
-       Ok(Contribution { heat_level })
+       let count = Self::parse_count(&Self::tooltip_text(el))?;
+       Ok(Contribution { date, heat_level, count })
     }
 
     /// Renders a contribution node. 
     ///
     /// Returns a formatted string containing a Unicode box character, 
-    /// with a fill color depending on the provided [`ColorValues`] variant, 
+    /// with a fill color depending on the provided [`Palette`], 
     /// and the `heat_level` property of the `Contribution` instance.
+    /// Palettes without color support shade the node with a glyph instead.
+    ///
+    pub fn render(&self, palette: &Palette) -> String {
+       palette.paint("\u{025A0} ", self.heat_level)
+    }
+
+    /// Describes the contribution in the same wording as Github's tooltips,
+    /// e.g. "4 contributions on Sunday, September 18, 2022".
     ///
-    pub fn render(&self, color: &ColorValues) -> String {
-       let intensity = match self.heat_level {
-           0 => 0,
-           1 => 64,
-           2 => 127,
-           3 => 191,
-           _ => 255,
-       };
+    pub fn tooltip(&self) -> String {
+        let date = self.date.format("%A, %B %-d, %Y");
+
+        match self.count {
+            0 => format!("No contributions on {date}"),
+            1 => format!("1 contribution on {date}"),
+            count => format!("{count} contributions on {date}"),
+        }
+    }
+
+    fn tooltip_text(el: &ElementRef) -> String {
+        // Older markup nests the tooltip text inside the node itself, whereas newer
+        // markup renders it in a sibling `tool-tip` (or `span.sr-only`) element.
+        let own_text = el.text().collect::<String>();
+
+        if !own_text.trim().is_empty() {
+            return own_text.trim().to_string();
+        }
+
+        el.next_siblings()
+            .filter_map(ElementRef::wrap)
+            .take_while(|sibling| sibling.value().name() != el.value().name())
+            .find(|sibling| TOOLTIP_ELEMENTS.contains(&sibling.value().name()))
+            .map(|sibling| sibling.text().collect::<String>().trim().to_string())
+            .unwrap_or_default()
+    }
+
+    fn parse_count(text: &str) -> Result<usize, HeatmapError> {
+        let parse_error = || HeatmapError::ParseContributionCount(text.to_string());
+        let mut words = text.split_whitespace();
+
+        let count = match words.next().ok_or_else(parse_error)? {
+            "No" | "no" => 0,
+            value => value.replace(',', "").parse().map_err(|_| parse_error())?,
+        };
+
+        match words.next() {
+            Some("contribution" | "contributions") => Ok(count),
+            _ => Err(parse_error()),
+        }
+    }
+
+    fn parse_date(el: &ElementRef) -> Result<NaiveDate, HeatmapError> {
+        let value = el
+           .value()
+           .attr(DATE_ATTR)
+           .ok_or_else(|| HeatmapError::QueryAttribute {
+               attr: DATE_ATTR.to_string(),
+               on_alias: "heatmap node".to_string()
+           })?;
 
-       let fill = match color {
-           ColorValues::Red => Color::TrueColor { r: intensity, g: 0, b: 0 },
-           ColorValues::Green => Color::TrueColor { r: 0, g: intensity, b: 0 },
-           ColorValues::Blue => Color::TrueColor { r: 0, g: 0, b: intensity },
-       };
+        let date = NaiveDate::parse_from_str(value, DATE_FORMAT)
+           .map_err(|_| HeatmapError::ParseAttribute {
+               attr: DATE_ATTR.to_string(),
+               on_alias: "heatmap node".to_string()
+           })?;
 
-       "\u{025A0} ".color(fill).to_string()
+        Ok(date)
     }
 
     fn parse_heat_level(el: &ElementRef) -> Result<usize, HeatmapError> {
@@ -81,21 +149,63 @@ impl Contribution {
 #[cfg(test)] 
 mod tests {
     use super::*;
+    use colored::{Color, Colorize};
     use scraper::{Html, Selector};
+    use crate::ColorValues;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, DATE_FORMAT).unwrap()
+    }
 
     #[test]
     fn constructs_contribution() {
-        let fragment = Html::parse_fragment("<rect y='15' data-level='3' />");
+        let fragment = Html::parse_fragment("<rect y='15' data-date='2022-09-18' data-level='3'>3 contributions on 2022-09-18</rect>");
         let selector = Selector::parse("rect").unwrap();
         let rect_el = fragment.select(&selector).next().unwrap();
         let contribution = Contribution::from_el(&rect_el).unwrap();
 
-        assert_eq!(contribution, Contribution { heat_level: 3 })
+        assert_eq!(contribution, Contribution { date: date("2022-09-18"), heat_level: 3, count: 3 })
+    }
+
+    #[test]
+    fn parses_date_attribute() {
+        let fragment = Html::parse_fragment("<rect y='15' data-date='2022-09-18' data-level='3'>3 contributions on 2022-09-18</rect>");
+        let selector = Selector::parse("rect").unwrap();
+        let rect_el = fragment.select(&selector).next().unwrap();
+        let parsed = Contribution::parse_date(&rect_el).unwrap();
+
+        assert_eq!(parsed, date("2022-09-18"))
+    }
+
+    #[test]
+    fn error_if_no_date_attribute() {
+        let fragment = Html::parse_fragment("<rect y='15' data-level='3'>No contributions</rect>");
+        let selector = Selector::parse("rect").unwrap();
+        let rect_el = fragment.select(&selector).next().unwrap();
+        let parsed = Contribution::parse_date(&rect_el);
+
+        assert_eq!(
+            parsed,
+            Err(HeatmapError::QueryAttribute { attr: DATE_ATTR.to_string(), on_alias: "heatmap node".to_string() })
+        )
+    }
+
+    #[test]
+    fn error_if_invalid_date_attribute() {
+        let fragment = Html::parse_fragment("<rect y='15' data-date='18/09/2022' data-level='3'>3 contributions on 18/09/2022</rect>");
+        let selector = Selector::parse("rect").unwrap();
+        let rect_el = fragment.select(&selector).next().unwrap();
+        let parsed = Contribution::parse_date(&rect_el);
+
+        assert_eq!(
+            parsed,
+            Err(HeatmapError::ParseAttribute { attr: DATE_ATTR.to_string(), on_alias: "heatmap node".to_string() })
+        )
     }
 
     #[test]
     fn parses_level_attribute() {
-        let fragment = Html::parse_fragment("<rect y='15' data-level='3' />");
+        let fragment = Html::parse_fragment("<rect y='15' data-level='3'>No contributions</rect>");
         let selector = Selector::parse("rect").unwrap();
         let rect_el = fragment.select(&selector).next().unwrap();
         let heat_level = Contribution::parse_heat_level(&rect_el).unwrap();
@@ -105,7 +215,7 @@ mod tests {
 
     #[test]
     fn error_if_no_level_attribute() {
-        let fragment = Html::parse_fragment("<rect y='15' data-heat-level='3' />");
+        let fragment = Html::parse_fragment("<rect y='15' data-heat-level='3'>No contributions</rect>");
         let selector = Selector::parse("rect").unwrap();
         let rect_el = fragment.select(&selector).next().unwrap();
         let heat_level = Contribution::parse_heat_level(&rect_el);
@@ -118,7 +228,7 @@ mod tests {
 
     #[test]
     fn error_if_invalid_level_attribute() {
-        let fragment = Html::parse_fragment("<rect y='15' data-level='three' />");
+        let fragment = Html::parse_fragment("<rect y='15' data-level='three'>No contributions</rect>");
         let selector = Selector::parse("rect").unwrap();
         let rect_el = fragment.select(&selector).next().unwrap();
         let heat_level = Contribution::parse_heat_level(&rect_el);
@@ -129,40 +239,95 @@ mod tests {
         )
     }
 
+    #[test]
+    fn parses_no_contributions() {
+        let count = Contribution::parse_count("No contributions on Sunday, September 18, 2022").unwrap();
+        assert_eq!(count, 0)
+    }
+
+    #[test]
+    fn parses_singular_contribution() {
+        let count = Contribution::parse_count("1 contribution on Sunday, September 18, 2022").unwrap();
+        assert_eq!(count, 1)
+    }
+
+    #[test]
+    fn parses_plural_contributions() {
+        let count = Contribution::parse_count("1,024 contributions on Sunday, September 18, 2022").unwrap();
+        assert_eq!(count, 1024)
+    }
+
+    #[test]
+    fn error_if_invalid_contribution_text() {
+        let text = "Many commits on Sunday, September 18, 2022";
+        let count = Contribution::parse_count(text);
+
+        assert_eq!(count, Err(HeatmapError::ParseContributionCount(text.to_string())))
+    }
+
+    #[test]
+    fn reads_count_from_sibling_tooltip() {
+        let fragment = Html::parse_fragment(r#"
+            <rect data-date='2022-09-18' data-level='2'></rect>
+            <tool-tip>7 contributions on September 18th.</tool-tip>
+            <rect data-date='2022-09-19' data-level='0'></rect>
+            <tool-tip>No contributions on September 19th.</tool-tip>
+        "#);
+        let selector = Selector::parse("rect").unwrap();
+        let contributions: Vec<_> = fragment
+            .select(&selector)
+            .map(|el| Contribution::from_el(&el).unwrap().count)
+            .collect();
+
+        assert_eq!(contributions, vec![7, 0])
+    }
+
+    #[test]
+    fn describes_contribution_as_tooltip() {
+        let none = Contribution { date: date("2022-09-18"), heat_level: 0, count: 0 };
+        let one = Contribution { date: date("2022-09-18"), heat_level: 1, count: 1 };
+        let many = Contribution { date: date("2022-09-18"), heat_level: 4, count: 12 };
+
+        assert_eq!(none.tooltip(), "No contributions on Sunday, September 18, 2022");
+        assert_eq!(one.tooltip(), "1 contribution on Sunday, September 18, 2022");
+        assert_eq!(many.tooltip(), "12 contributions on Sunday, September 18, 2022");
+        assert_eq!(Contribution::parse_count(&many.tooltip()), Ok(12));
+    }
+
     #[test]
     fn renders_heatmap_node_unfilled() {
-        let contribution = Contribution { heat_level: 0 };
-        let color = ColorValues::Green;
+        let contribution = Contribution { date: date("2022-09-18"), heat_level: 0, count: 0 };
+        let palette = Palette::from(&ColorValues::Green);
         let expected = "\u{025A0} ".color(Color::TrueColor { r: 0, g: 0, b: 0 }).to_string();
 
-        assert_eq!(contribution.render(&color), expected);
+        assert_eq!(contribution.render(&palette), expected);
     }
     
     #[test]
     fn renders_heatmap_node_red() {
-        let contribution = Contribution { heat_level: 1 };
-        let color = ColorValues::Red;
+        let contribution = Contribution { date: date("2022-09-18"), heat_level: 1, count: 1 };
+        let palette = Palette::from(&ColorValues::Red);
         let expected = "\u{025A0} ".color(Color::TrueColor { r: 64, g: 0, b: 0 }).to_string();
 
-        assert_eq!(contribution.render(&color), expected);
+        assert_eq!(contribution.render(&palette), expected);
     }
 
 
     #[test]
     fn renders_heatmap_node_green() {
-        let contribution = Contribution { heat_level: 2 };
-        let color = ColorValues::Green;
+        let contribution = Contribution { date: date("2022-09-18"), heat_level: 2, count: 2 };
+        let palette = Palette::from(&ColorValues::Green);
         let expected = "\u{025A0} ".color(Color::TrueColor { r: 0, g: 127, b: 0 }).to_string();
 
-        assert_eq!(contribution.render(&color), expected);
+        assert_eq!(contribution.render(&palette), expected);
     }
 
     #[test]
     fn renders_heatmap_node_blue() {
-        let contribution = Contribution { heat_level: 3 };
-        let color = ColorValues::Blue;
+        let contribution = Contribution { date: date("2022-09-18"), heat_level: 3, count: 3 };
+        let palette = Palette::from(&ColorValues::Blue);
         let expected = "\u{025A0} ".color(Color::TrueColor { r: 0, g: 0, b: 191 }).to_string();
 
-        assert_eq!(contribution.render(&color), expected);
+        assert_eq!(contribution.render(&palette), expected);
     }
 }
@@ -1,6 +1,6 @@
 use scraper::ElementRef;
 use crate::HeatmapError;
-use super::Contribution;
+use super::{Contribution, DAYS_IN_WEEK};
 
 const Y_ATTR: &str = "y";
 
@@ -12,6 +12,7 @@ const Y_ATTR: &str = "y";
 /// implicitly by the higher level `Heatmap` struct via the `from_days` associated method.
 ///
 #[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContributionWeek {
     /// A vector of [`Contribution`] instances belonging to the week. 
     ///
@@ -32,12 +33,12 @@ impl ContributionWeek {
     /// - [`HeatmapError::QueryAttribute`] fails to query y attribute
     /// - [`HeatmapError::ParseAttribute`] fails to parse y attribute
     /// - [`HeatmapError::UnknownNodeFormat`] encounters unexpected heatmap node size while
-    /// determining day of week for contributions
+    ///   determining day of week for contributions
     ///
     /// See [`Contribution`] for possible errors related to constructing a ['Contribution'].
     ///
     pub fn from_days(days: &Vec<ElementRef>) -> Result<Self, HeatmapError> {
-        let mut contributions: Vec<Option<Contribution>> = vec![None; 7]; 
+        let mut contributions: Vec<Option<Contribution>> = vec![None; DAYS_IN_WEEK]; 
 
         for day in days {
             let y_value = Self::parse_y_attr(day)?;
@@ -82,18 +83,23 @@ impl ContributionWeek {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::NaiveDate;
     use scraper::{Html, Selector};
 
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
     #[test]
     fn constructs_contribution_week() {
         let fragment = Html::parse_fragment(r#"
-            <rect y='0' data-level='1' />
-            <rect y='15' data-level='2' />
-            <rect y='30' data-level='3' />
-            <rect y='45' data-level='4' />
-            <rect y='60' data-level='4' />
-            <rect y='75' data-level='4' />
-            <rect y='90' data-level='4' />
+            <rect y='0' data-date='2022-09-18' data-level='1'>1 contribution on 2022-09-18</rect>
+            <rect y='15' data-date='2022-09-19' data-level='2'>2 contributions on 2022-09-19</rect>
+            <rect y='30' data-date='2022-09-20' data-level='3'>3 contributions on 2022-09-20</rect>
+            <rect y='45' data-date='2022-09-21' data-level='4'>4 contributions on 2022-09-21</rect>
+            <rect y='60' data-date='2022-09-22' data-level='4'>4 contributions on 2022-09-22</rect>
+            <rect y='75' data-date='2022-09-23' data-level='4'>4 contributions on 2022-09-23</rect>
+            <rect y='90' data-date='2022-09-24' data-level='4'>4 contributions on 2022-09-24</rect>
         "#);
 
         let selector = Selector::parse("rect").unwrap();
@@ -102,13 +108,13 @@ mod tests {
 
         let expected = ContributionWeek {
             contributions: vec![
-                Some(Contribution { heat_level: 1 }),
-                Some(Contribution { heat_level: 2 }),
-                Some(Contribution { heat_level: 3 }),
-                Some(Contribution { heat_level: 4 }),
-                Some(Contribution { heat_level: 4 }), 
-                Some(Contribution { heat_level: 4 }),
-                Some(Contribution { heat_level: 4 }),
+                Some(Contribution { date: date("2022-09-18"), heat_level: 1, count: 1 }),
+                Some(Contribution { date: date("2022-09-19"), heat_level: 2, count: 2 }),
+                Some(Contribution { date: date("2022-09-20"), heat_level: 3, count: 3 }),
+                Some(Contribution { date: date("2022-09-21"), heat_level: 4, count: 4 }),
+                Some(Contribution { date: date("2022-09-22"), heat_level: 4, count: 4 }), 
+                Some(Contribution { date: date("2022-09-23"), heat_level: 4, count: 4 }),
+                Some(Contribution { date: date("2022-09-24"), heat_level: 4, count: 4 }),
             ]
         };
 
@@ -118,9 +124,9 @@ mod tests {
     #[test]
     fn constructs_partial_contribution_week() {
         let fragment = Html::parse_fragment(r#"
-            <rect y='60' data-level='1' />
-            <rect y='75' data-level='2' />
-            <rect y='90' data-level='3' />
+            <rect y='60' data-date='2022-09-22' data-level='1'>1 contribution on 2022-09-22</rect>
+            <rect y='75' data-date='2022-09-23' data-level='2'>2 contributions on 2022-09-23</rect>
+            <rect y='90' data-date='2022-09-24' data-level='3'>3 contributions on 2022-09-24</rect>
         "#);
 
         let selector = Selector::parse("rect").unwrap();
@@ -133,9 +139,9 @@ mod tests {
                 None,
                 None,
                 None,
-                Some(Contribution { heat_level: 1 }), 
-                Some(Contribution { heat_level: 2 }),
-                Some(Contribution { heat_level: 3 }),
+                Some(Contribution { date: date("2022-09-22"), heat_level: 1, count: 1 }), 
+                Some(Contribution { date: date("2022-09-23"), heat_level: 2, count: 2 }),
+                Some(Contribution { date: date("2022-09-24"), heat_level: 3, count: 3 }),
             ]
         };
 
@@ -144,7 +150,7 @@ mod tests {
 
     #[test]
     fn parses_y_attribute() {
-        let fragment = Html::parse_fragment("<rect y='15' data-level='3' />");
+        let fragment = Html::parse_fragment("<rect y='15' data-date='2022-09-19' data-level='3'>3 contributions on 2022-09-19</rect>");
         let selector = Selector::parse("rect").unwrap();
         let rect_el = fragment.select(&selector).next().unwrap();
         let y_value = ContributionWeek::parse_y_attr(&rect_el).unwrap();
@@ -154,7 +160,7 @@ mod tests {
 
     #[test]
     fn error_if_no_y_attribute() {
-        let fragment = Html::parse_fragment("<rect data-level='3' />");
+        let fragment = Html::parse_fragment("<rect data-level='3'>No contributions</rect>");
         let selector = Selector::parse("rect").unwrap();
         let rect_el = fragment.select(&selector).next().unwrap();
         let y_value = ContributionWeek::parse_y_attr(&rect_el);
@@ -167,7 +173,7 @@ mod tests {
 
     #[test]
     fn error_if_invalid_y_attribute() {
-        let fragment = Html::parse_fragment("<rect y='fifteen' data-level='three' />");
+        let fragment = Html::parse_fragment("<rect y='fifteen' data-level='three'>No contributions</rect>");
         let selector = Selector::parse("rect").unwrap();
         let rect_el = fragment.select(&selector).next().unwrap();
         let contribution = ContributionWeek::parse_y_attr(&rect_el);
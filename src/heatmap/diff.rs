@@ -0,0 +1,329 @@
+use std::io::{self, Write};
+use chrono::Duration;
+use crate::Stats;
+use super::aggregate::{quartile_level, quartiles};
+use super::{
+    ColorDepth, Contribution, GridOptions, Heatmap, CELL_WIDTH, DAYS_IN_WEEK, WEEKDAY_LABELS,
+    WEEKDAY_LABEL_WIDTH,
+};
+
+const LEFT_STOPS: [(u8, u8, u8); 4] = [(0x5c, 0x2e, 0x0e), (0x9a, 0x4a, 0x0f), (0xd9, 0x6c, 0x1a), (0xff, 0x9f, 0x43)];
+const RIGHT_STOPS: [(u8, u8, u8); 4] = [(0x0e, 0x44, 0x29), (0x00, 0x6d, 0x32), (0x26, 0xa6, 0x41), (0x39, 0xd3, 0x53)];
+const NEUTRAL: (u8, u8, u8) = (0x30, 0x36, 0x3d);
+
+/// A `HeatmapDiff` compares two heatmaps day by day, such as the heatmaps of
+/// two profiles, or of the same profile in two years.
+///
+/// Days are matched by weekday and week offset: the right heatmap is shifted by
+/// the whole number of weeks closest to the distance between the first days of
+/// both heatmaps, so that e.g. each day of a year is compared to the same
+/// weekday of the following year, at most three days from the same date.
+///
+#[derive(Debug, Eq, PartialEq)]
+pub struct HeatmapDiff {
+    /// Heatmap drawn with the left-hand, orange side of the diverging palette.
+    pub left: Heatmap,
+    /// Heatmap drawn with the right-hand, green side of the diverging palette.
+    pub right: Heatmap,
+    /// Label of the left heatmap, used by the legend and summary.
+    pub left_label: String,
+    /// Label of the right heatmap, used by the legend and summary.
+    pub right_label: String,
+}
+
+impl HeatmapDiff {
+    /// Constructs a new `HeatmapDiff` from two heatmaps, labelled `left` and
+    /// `right` until [`HeatmapDiff::with_labels`] is used.
+    ///
+    /// Both heatmaps are laid out again over the same number of weeks, with
+    /// matching days in the same column and row.
+    ///
+    pub fn new(left: Heatmap, right: Heatmap) -> Self {
+        let (left, right) = match (left.date_bounds(), right.date_bounds()) {
+            (Some((left_from, left_to)), Some((right_from, right_to))) => {
+                let weeks = ((right_from - left_from).num_days() as f64 / DAYS_IN_WEEK as f64).round() as i64;
+                let shift = Duration::weeks(weeks);
+                let from = left_from.min(right_from - shift);
+                let to = left_to.max(right_to - shift);
+
+                (left.aligned_to(from, to), right.aligned_to(from + shift, to + shift))
+            },
+            _ => (left, right),
+        };
+
+        HeatmapDiff {
+            left,
+            right,
+            left_label: String::from("left"),
+            right_label: String::from("right"),
+        }
+    }
+
+    /// Sets the labels of both heatmaps, e.g. profile slugs or years.
+    pub fn with_labels(mut self, left: &str, right: &str) -> Self {
+        self.left_label = left.to_string();
+        self.right_label = right.to_string();
+        self
+    }
+
+    /// Number of weeks covered by the longer of both heatmaps.
+    pub fn weeks(&self) -> usize {
+        self.left.contribution_weeks.len().max(self.right.contribution_weeks.len())
+    }
+
+    /// Returns the difference in contributions between the right and left
+    /// heatmaps on the provided weekday (starting on Sunday) of the provided
+    /// week, or `None` if neither heatmap covers that day.
+    ///
+    /// Days covered by only one of the heatmaps count as no contributions for
+    /// the other.
+    ///
+    pub fn delta(&self, week: usize, day: usize) -> Option<i64> {
+        let count = |heatmap: &Heatmap| heatmap.contribution_weeks
+            .get(week)
+            .and_then(|week| week.contributions.get(day)?.as_ref())
+            .map(|contribution: &Contribution| contribution.count as i64);
+
+        match (count(&self.left), count(&self.right)) {
+            (None, None) => None,
+            (left, right) => Some(right.unwrap_or(0) - left.unwrap_or(0)),
+        }
+    }
+
+    /// Counts the days on which the left heatmap was more active, the days on
+    /// which both were equally active, and the days on which the right heatmap
+    /// was more active.
+    pub fn tally(&self) -> (usize, usize, usize) {
+        self.deltas().fold((0, 0, 0), |(left, equal, right), delta| match delta {
+            delta if delta < 0 => (left + 1, equal, right),
+            0 => (left, equal + 1, right),
+            _ => (left, equal, right + 1),
+        })
+    }
+
+    /// Generates the diverging grid, and writes it to the provided writer, one
+    /// row per day of the week.
+    ///
+    /// Days on which the left heatmap was more active are shaded orange, days
+    /// on which the right heatmap was more active are shaded green, and the
+    /// intensity depends on the quartile of the difference. Month labels follow
+    /// the left heatmap. In [`ColorDepth::Monochrome`] mode, days are drawn as
+    /// `<`, `=` and `>` instead.
+    ///
+    /// # Errors
+    /// Returns an error if writing to the provided writer fails.
+    ///
+    pub fn render_to(&self, writer: &mut impl Write, depth: ColorDepth, options: &GridOptions) -> io::Result<()> {
+        let thresholds = quartiles(self.deltas().map(i64::unsigned_abs).map(|delta| delta as usize));
+        let margin = match options.weekday_labels {
+            true => WEEKDAY_LABEL_WIDTH,
+            false => 0,
+        };
+
+        if options.month_labels {
+            writeln!(writer, "{:margin$}{}", "", self.left.render_month_labels().trim_end())?;
+        }
+
+        for (day, label) in WEEKDAY_LABELS.iter().enumerate() {
+            let week: String = (0..self.weeks())
+                .map(|week| match self.delta(week, day) {
+                    Some(delta) => Self::render_cell(depth, delta.signum(), quartile_level(delta.unsigned_abs() as usize, &thresholds)),
+                    None => String::from("  "),
+                })
+                .collect();
+
+            match options.weekday_labels {
+                true => writeln!(writer, "{label:margin$}{week}")?,
+                false => writeln!(writer, "{week}")?,
+            }
+        }
+
+        if options.legend {
+            let levels: String = (1..=4).rev().map(|level| Self::render_cell(depth, -1, level))
+                .chain(std::iter::once(Self::render_cell(depth, 0, 0)))
+                .chain((1..=4).map(|level| Self::render_cell(depth, 1, level)))
+                .collect();
+
+            writeln!(writer, "{:margin$}{} more {levels}{} more", "", self.left_label, self.right_label)?;
+        }
+
+        Ok(())
+    }
+
+    /// Describes the differences between the [`Stats`] of both heatmaps as
+    /// rows of a label, the left value, the right value and the change from
+    /// left to right.
+    pub fn summary(&self) -> Vec<(&'static str, String, String, String)> {
+        let (left, right) = (Stats::from_heatmap(&self.left), Stats::from_heatmap(&self.right));
+        let streak = |stats: &Stats| stats.longest_streak.map_or(0, |streak| streak.days());
+        let busiest = |stats: &Stats| stats.busiest_day.as_ref().map_or(0, |day| day.count);
+        let (more_left, equal, more_right) = self.tally();
+
+        let count_row = |label, left: usize, right: usize| {
+            (label, left.to_string(), right.to_string(), change(left as f64, right as f64, 0))
+        };
+
+        let mut total = count_row("Total contributions", left.total, right.total);
+        if left.total > 0 {
+            let percent = (right.total as f64 - left.total as f64) / left.total as f64 * 100.0;
+            total.3 = format!("{} ({percent:+.1}%)", total.3);
+        }
+
+        vec![
+            total,
+            count_row("Active days", left.active_days, right.active_days),
+            count_row("Longest streak", streak(&left), streak(&right)),
+            count_row("Busiest day", busiest(&left), busiest(&right)),
+            (
+                "Mean per active day",
+                format!("{:.1}", left.mean_per_active_day),
+                format!("{:.1}", right.mean_per_active_day),
+                change(left.mean_per_active_day, right.mean_per_active_day, 1),
+            ),
+            ("More active days", more_left.to_string(), more_right.to_string(), format!("{equal} equal")),
+        ]
+    }
+
+    /// Writes the [`HeatmapDiff::summary`] to the provided writer as a table
+    /// with aligned columns, headed by the labels of both heatmaps.
+    ///
+    /// # Errors
+    /// Returns an error if writing to the provided writer fails.
+    ///
+    pub fn render_summary_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        let summary = self.summary();
+        let width = |column: fn(&(&'static str, String, String, String)) -> usize, header: usize| summary
+            .iter()
+            .map(column)
+            .max()
+            .unwrap_or(0)
+            .max(header);
+
+        let label_width = width(|row| row.0.len(), 0);
+        let left_width = width(|row| row.1.chars().count(), self.left_label.chars().count());
+        let right_width = width(|row| row.2.chars().count(), self.right_label.chars().count());
+
+        writeln!(writer, "{:label_width$}  {:>left_width$}  {:>right_width$}  Change", "", self.left_label, self.right_label)?;
+
+        for (label, left, right, change) in summary {
+            writeln!(writer, "{label:label_width$}  {left:>left_width$}  {right:>right_width$}  {change}")?;
+        }
+
+        Ok(())
+    }
+
+    fn deltas(&self) -> impl Iterator<Item = i64> + '_ {
+        (0..self.weeks()).flat_map(move |week| (0..DAYS_IN_WEEK).filter_map(move |day| self.delta(week, day)))
+    }
+
+    fn render_cell(depth: ColorDepth, sign: i64, level: usize) -> String {
+        let (glyph, rgb) = match sign {
+            sign if sign < 0 => ('<', LEFT_STOPS[level - 1]),
+            0 => ('=', NEUTRAL),
+            _ => ('>', RIGHT_STOPS[level - 1]),
+        };
+
+        match depth {
+            ColorDepth::Monochrome => format!("{glyph}{:1$}", "", CELL_WIDTH - 1),
+            depth => depth.paint("\u{025A0} ", rgb, level),
+        }
+    }
+}
+
+/// Formats the signed change from one value to another with the provided
+/// number of decimals.
+fn change(left: f64, right: f64, decimals: usize) -> String {
+    format!("{:+.decimals$}", right - left)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    /// Builds a heatmap of consecutive days starting on the provided date.
+    fn heatmap(start: &str, counts: &[usize]) -> Heatmap {
+        let start = date(start);
+
+        Heatmap::from_contributions(counts
+            .iter()
+            .zip(start.iter_days())
+            .map(|(count, date)| Contribution { date, heat_level: (*count).min(4), count: *count })
+            .collect())
+    }
+
+    #[test]
+    fn aligns_days_by_week_offset_and_weekday() {
+        let diff = HeatmapDiff::new(
+            heatmap("2022-01-02", &[1, 0, 3, 0, 0, 0, 0, 2]),
+            heatmap("2023-01-01", &[0, 0, 5]),
+        );
+
+        assert_eq!(diff.weeks(), 2);
+        assert_eq!(diff.delta(0, 0), Some(-1));
+        assert_eq!(diff.delta(0, 1), Some(0));
+        assert_eq!(diff.delta(0, 2), Some(2));
+        assert_eq!(diff.delta(1, 0), Some(-2));
+        assert_eq!(diff.delta(1, 1), None);
+        assert_eq!(diff.tally(), (2, 5, 1));
+    }
+
+    #[test]
+    fn aligns_years_starting_on_different_weekdays() {
+        // January 1st falls on a Saturday in 2022, and on a Sunday in 2023.
+        let diff = HeatmapDiff::new(heatmap("2022-01-01", &[5, 1, 2]), heatmap("2023-01-01", &[1, 4]));
+
+        assert_eq!(diff.weeks(), 2);
+        assert_eq!(diff.left.contribution_weeks[1].contributions[0].as_ref().map(|day| day.date), Some(date("2022-01-02")));
+        assert_eq!(diff.right.contribution_weeks[1].contributions[0].as_ref().map(|day| day.date), Some(date("2023-01-01")));
+        assert_eq!(diff.delta(1, 0), Some(0));
+        assert_eq!(diff.delta(1, 1), Some(2));
+        assert_eq!(diff.delta(0, 6), Some(-5));
+    }
+
+    #[test]
+    fn renders_diverging_grid() {
+        let diff = HeatmapDiff::new(heatmap("2022-01-02", &[2, 1, 1]), heatmap("2023-01-01", &[0, 1, 4]))
+            .with_labels("2022", "2023");
+
+        let options = GridOptions { month_labels: false, ..GridOptions::default() };
+        let mut output = vec![];
+        diff.render_to(&mut output, ColorDepth::Monochrome, &options).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), [
+            "    < ",
+            "Mon = ",
+            "    > ",
+            "Wed   ",
+            "      ",
+            "Fri   ",
+            "      ",
+            "    2022 more < < < < = > > > > 2023 more",
+            "",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn summarizes_deltas() {
+        let diff = HeatmapDiff::new(heatmap("2022-01-02", &[2, 2, 0, 4]), heatmap("2023-01-01", &[3, 3, 3, 3]))
+            .with_labels("alice", "bob");
+
+        let summary = diff.summary();
+
+        assert_eq!(summary[0], ("Total contributions", "8".to_string(), "12".to_string(), "+4 (+50.0%)".to_string()));
+        assert_eq!(summary[1], ("Active days", "3".to_string(), "4".to_string(), "+1".to_string()));
+        assert_eq!(summary[2], ("Longest streak", "2".to_string(), "4".to_string(), "+2".to_string()));
+        assert_eq!(summary[5], ("More active days", "1".to_string(), "3".to_string(), "0 equal".to_string()));
+
+        let mut output = vec![];
+        diff.render_summary_to(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("                     alice  bob  Change\n"));
+        assert!(output.contains("Total contributions      8   12  +4 (+50.0%)\n"));
+    }
+}
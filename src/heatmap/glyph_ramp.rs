@@ -0,0 +1,80 @@
+use super::{CellRenderer, HEAT_LEVELS};
+
+const DEFAULT_GLYPHS: &str = "·░▒▓█";
+
+/// A `GlyphRamp` maps each heat level of a [`super::Contribution`] to a glyph of
+/// increasing density, for output that cannot rely on color such as logs,
+/// emails or screen readers.
+///
+/// Ramps of any length are stretched or compressed over the five heat levels,
+/// so `·░▒▓█` and `·•●` are both valid ramps.
+///
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GlyphRamp {
+    /// Glyphs ordered from least to most contributions.
+    pub glyphs: Vec<char>,
+}
+
+impl GlyphRamp {
+    /// Constructs a new `GlyphRamp` instance from a string of glyphs ordered
+    /// from least to most contributions, e.g. ` ░▒▓█`.
+    ///
+    /// # Errors
+    /// Returns an error if the ramp contains fewer than two glyphs.
+    ///
+    pub fn new(glyphs: &str) -> Result<Self, String> {
+        let glyphs: Vec<char> = glyphs.chars().collect();
+
+        match glyphs.len() {
+            0 | 1 => Err(format!("glyph ramp must contain at least 2 glyphs, found {}", glyphs.len())),
+            _ => Ok(GlyphRamp { glyphs })
+        }
+    }
+
+    /// Returns the glyph used to shade a heatmap node of the provided heat
+    /// level. Levels above 4 are shaded like level 4.
+    pub fn glyph(&self, heat_level: usize) -> char {
+        let level = heat_level.min(HEAT_LEVELS - 1);
+        let last = self.glyphs.len() - 1;
+
+        self.glyphs[(level * last + (HEAT_LEVELS - 1) / 2) / (HEAT_LEVELS - 1)]
+    }
+}
+
+impl Default for GlyphRamp {
+    fn default() -> Self {
+        GlyphRamp::new(DEFAULT_GLYPHS).unwrap()
+    }
+}
+
+impl CellRenderer for GlyphRamp {
+    fn render_level(&self, heat_level: usize) -> String {
+        format!("{} ", self.glyph(heat_level))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_heat_levels_to_glyphs() {
+        let ramp = GlyphRamp::new(" ░▒▓█").unwrap();
+        let glyphs: String = (0..6).map(|level| ramp.glyph(level)).collect();
+
+        assert_eq!(glyphs, " ░▒▓██");
+    }
+
+    #[test]
+    fn stretches_short_ramps_over_heat_levels() {
+        let ramp = GlyphRamp::new("·•●").unwrap();
+        let glyphs: String = (0..5).map(|level| ramp.glyph(level)).collect();
+
+        assert_eq!(glyphs, "·••●●");
+    }
+
+    #[test]
+    fn error_if_too_few_glyphs() {
+        assert_eq!(GlyphRamp::new("█"), Err("glyph ramp must contain at least 2 glyphs, found 1".to_string()));
+    }
+}
@@ -0,0 +1,32 @@
+/// Layout options used while rendering a [`super::Heatmap`] as a terminal
+/// grid with [`super::Heatmap::render_to`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GridOptions {
+    /// Print abbreviated month names above the first week of each month.
+    pub month_labels: bool,
+    /// Print Mon, Wed and Fri labels to the left of their rows.
+    pub weekday_labels: bool,
+    /// Print a "Less ... More" legend of every heat level below the grid.
+    pub legend: bool,
+}
+
+impl GridOptions {
+    /// Options rendering the bare grid, without any labels or legend.
+    pub fn plain() -> Self {
+        GridOptions {
+            month_labels: false,
+            weekday_labels: false,
+            legend: false,
+        }
+    }
+}
+
+impl Default for GridOptions {
+    fn default() -> Self {
+        GridOptions {
+            month_labels: true,
+            weekday_labels: true,
+            legend: true,
+        }
+    }
+}
@@ -1,14 +1,47 @@
 mod contribution_week;
 mod contribution;
+mod color_depth;
+mod aggregate;
+mod glyph_ramp;
+mod diff;
+mod grid_options;
+mod palette;
 
 pub use contribution_week::ContributionWeek;
 pub use contribution::Contribution;
+pub use color_depth::ColorDepth;
+pub use glyph_ramp::GlyphRamp;
+pub use diff::HeatmapDiff;
+pub use grid_options::GridOptions;
+pub use palette::{Palette, PALETTE_PRESETS};
+use std::io::{self, Write};
+use chrono::{Datelike, Duration, NaiveDate};
 use scraper::{Selector, Html, ElementRef};
 use crate::{ColorValues, HeatmapError};
 
 const WEEK_SELECTOR: &str = "svg.js-calendar-graph-svg g g";
 const DAY_SELECTOR: &str = "rect.ContributionCalendar-day";
-const DAYS_IN_WEEK: usize = 7;
+pub(crate) const DAYS_IN_WEEK: usize = 7;
+pub(crate) const HEAT_LEVELS: usize = 5;
+const CELL_WIDTH: usize = 2;
+const WEEKDAY_LABELS: [&str; DAYS_IN_WEEK] = ["", "Mon", "", "Wed", "", "Fri", ""];
+const WEEKDAY_LABEL_WIDTH: usize = 4;
+
+/// A `CellRenderer` draws a single heatmap node of the terminal grid, such as a
+/// colored box ([`Palette`]) or a glyph of increasing density ([`GlyphRamp`]).
+///
+/// Rendered nodes are expected to be two columns wide, matching the blank
+/// rendered for days missing from partial weeks.
+///
+pub trait CellRenderer {
+    /// Renders a node of the provided heat level, as used by the legend.
+    fn render_level(&self, heat_level: usize) -> String;
+
+    /// Renders the node of the provided [`Contribution`].
+    fn render_cell(&self, contribution: &Contribution) -> String {
+        self.render_level(contribution.heat_level)
+    }
+}
 
 /// A `Heatmap` instance represents a fully scraped and parsed Github
 /// constribution heatmap.
@@ -17,6 +50,7 @@ const DAYS_IN_WEEK: usize = 7;
 /// corresponding to a Github profile.
 ///
 #[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Heatmap {
     /// A vector of [`ContributionWeek`] instances spanning across the entire
     /// year of contributions.
@@ -62,24 +96,222 @@ impl Heatmap {
         }
     }
 
+    /// Constructs a new `Heatmap` instance from individual contributions, laid
+    /// out in weeks starting on Sunday in the same way as Github's calendar.
+    ///
+    /// Contributions are sorted by date. Days of the first and last weeks which
+    /// fall outside of the provided contributions, or dates missing in between,
+    /// are left empty.
+    ///
+    pub fn from_contributions(mut contributions: Vec<Contribution>) -> Self {
+        contributions.sort_by_key(|contribution| contribution.date);
+
+        match (contributions.first(), contributions.last()) {
+            (Some(first), Some(last)) => {
+                let (from, to) = (first.date, last.date);
+                Self::lay_out(contributions, from, to)
+            },
+            _ => Heatmap { contribution_weeks: vec![] },
+        }
+    }
+
+    /// Lays the contributions of the `Heatmap` out again over the weeks spanning
+    /// the provided dates, so that several heatmaps share the same columns.
+    ///
+    /// Days outside of the `Heatmap` are left empty, and contributions outside
+    /// of the provided dates are dropped.
+    ///
+    pub fn aligned_to(&self, from: NaiveDate, to: NaiveDate) -> Self {
+        let contributions = self.days()
+            .filter(|contribution| (from..=to).contains(&contribution.date))
+            .cloned()
+            .collect();
+
+        Self::lay_out(contributions, from, to)
+    }
+
+    /// Returns the earliest and latest dates covered by the `Heatmap`, if any.
+    pub fn date_bounds(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let mut dates = self.days().map(|contribution| contribution.date);
+
+        let first = dates.next()?;
+        let (from, to) = dates.fold((first, first), |(from, to), date| (from.min(date), to.max(date)));
+
+        Some((from, to))
+    }
+
+    fn lay_out(contributions: Vec<Contribution>, from: NaiveDate, to: NaiveDate) -> Self {
+        let first_sunday = from - Duration::days(from.weekday().num_days_from_sunday().into());
+        let weeks = ((to - first_sunday).num_days() / DAYS_IN_WEEK as i64) as usize + 1;
+        let mut contribution_weeks: Vec<ContributionWeek> = (0..weeks)
+            .map(|_| ContributionWeek { contributions: vec![None; DAYS_IN_WEEK] })
+            .collect();
+
+        for contribution in contributions {
+            let week = ((contribution.date - first_sunday).num_days() / DAYS_IN_WEEK as i64) as usize;
+            let day = contribution.date.weekday().num_days_from_sunday() as usize;
+
+            contribution_weeks[week].contributions[day] = Some(contribution);
+        }
+
+        Heatmap { contribution_weeks }
+    }
+
+    /// Looks up the [`Contribution`] made on a specific calendar date.
+    ///
+    /// Returns `None` if the provided date falls outside of the range of
+    /// dates covered by the `Heatmap`.
+    ///
+    pub fn get_contribution(&self, date: NaiveDate) -> Option<&Contribution> {
+        self.days().find(|contribution| contribution.date == date)
+    }
+
+    /// Iterates over the days of the `Heatmap` week by week, skipping the empty
+    /// days of partial weeks.
+    pub fn days(&self) -> impl Iterator<Item = &Contribution> {
+        self.contribution_weeks.iter().flat_map(|week| week.contributions.iter().flatten())
+    }
+
+    /// Iterates mutably over the days of the `Heatmap`, see [`Heatmap::days`].
+    pub fn days_mut(&mut self) -> impl Iterator<Item = &mut Contribution> {
+        self.contribution_weeks.iter_mut().flat_map(|week| week.contributions.iter_mut().flatten())
+    }
+
+    /// Determines the columns of the heatmap at which a new calendar month begins,
+    /// based on the earliest available day of each [`ContributionWeek`].
+    ///
+    /// Returns the index of each such week, along with its earliest date.
+    ///
+    pub fn month_starts(&self) -> Vec<(usize, NaiveDate)> {
+        let mut month_starts: Vec<(usize, NaiveDate)> = vec![];
+
+        for (index, week) in self.contribution_weeks.iter().enumerate() {
+            let first_day = week.contributions.iter().flatten().next();
+
+            if let Some(date) = first_day.map(|day| day.date) {
+                let new_month = month_starts
+                    .last()
+                    .is_none_or(|(_, previous)| (previous.year(), previous.month()) != (date.year(), date.month()));
+
+                if new_month {
+                    month_starts.push((index, date));
+                }
+            }
+        }
+
+        month_starts
+    }
+
     /// Generates visual representation of Heatmap data structure,
     /// and writes it to standard output.
     ///
     /// Resulting Unicode will have a fill color depending on provided
-    /// [`ColorValues`] color variant.
+    /// [`ColorValues`] color variant. Only the grid is printed, without labels
+    /// or legend; use [`Heatmap::render_with`] to configure both.
+    ///
+    /// # Panics
+    /// A panic will occur if writing to standard output fails, in the same way
+    /// as [`println!`]. Use [`Heatmap::render_to`] to handle such failures.
     ///
     pub fn render(&self, color: &ColorValues) {
-        for day in 0..DAYS_IN_WEEK {
+        self.render_with(&Palette::from(color), &GridOptions::plain());
+    }
+
+    /// Generates visual representation of Heatmap data structure,
+    /// and writes it to standard output.
+    ///
+    /// Each node is drawn by the provided [`CellRenderer`], e.g. a Unicode
+    /// box with a fill color depending on a [`Palette`]. Labels and legend
+    /// are printed according to the provided [`GridOptions`].
+    ///
+    /// # Panics
+    /// A panic will occur if writing to standard output fails, in the same way
+    /// as [`println!`]. Use [`Heatmap::render_to`] to handle such failures.
+    ///
+    pub fn render_with(&self, renderer: &impl CellRenderer, options: &GridOptions) {
+        self.render_to(&mut io::stdout().lock(), renderer, options)
+            .expect("failed printing heatmap to stdout");
+    }
+
+    /// Generates visual representation of Heatmap data structure,
+    /// and writes it to the provided writer, one row per day of the week.
+    ///
+    /// Each node is drawn by the provided [`CellRenderer`], e.g. a Unicode
+    /// box with a fill color depending on a [`Palette`]. Labels and legend
+    /// are printed according to the provided [`GridOptions`].
+    ///
+    /// # Errors
+    /// Returns an error if writing to the provided writer fails.
+    ///
+    pub fn render_to(
+        &self,
+        writer: &mut impl Write,
+        renderer: &impl CellRenderer,
+        options: &GridOptions
+    ) -> io::Result<()> {
+        let margin = match options.weekday_labels {
+            true => WEEKDAY_LABEL_WIDTH,
+            false => 0,
+        };
+
+        if options.month_labels {
+            writeln!(writer, "{:margin$}{}", "", self.render_month_labels().trim_end())?;
+        }
+
+        for (day, label) in WEEKDAY_LABELS.iter().enumerate() {
             let week: String = self.contribution_weeks
                 .iter()
                 .map(|week| match &week.contributions[day] {
-                    Some(day) => day.render(color),
+                    Some(day) => renderer.render_cell(day),
                     None => String::from("  ")
                 })
                 .collect();
 
-            println!("{week}");
+            match options.weekday_labels {
+                true => writeln!(writer, "{label:margin$}{week}")?,
+                false => writeln!(writer, "{week}")?,
+            }
         };
+
+        if options.legend {
+            let levels: String = (0..HEAT_LEVELS).map(|level| renderer.render_level(level)).collect();
+            let width = margin + self.contribution_weeks.len() * CELL_WIDTH;
+            let legend_width = "Less ".len() + HEAT_LEVELS * CELL_WIDTH + "More".len();
+
+            writeln!(writer, "{:indent$}Less {levels}More", "", indent = width.saturating_sub(legend_width))?;
+        }
+
+        Ok(())
+    }
+
+    /// Generates visual representation of Heatmap data structure,
+    /// and returns it as a `String`.
+    ///
+    /// See [`Heatmap::render_to`].
+    ///
+    pub fn render_to_string(&self, renderer: &impl CellRenderer, options: &GridOptions) -> String {
+        let mut output = vec![];
+        self.render_to(&mut output, renderer, options).expect("writing to a Vec cannot fail");
+
+        String::from_utf8(output).expect("rendered heatmap is valid UTF-8")
+    }
+
+    /// Lays out abbreviated month names above the week in which each month
+    /// starts, skipping labels which would overlap the previous one.
+    fn render_month_labels(&self) -> String {
+        let mut labels = String::new();
+
+        for (index, date) in self.month_starts() {
+            let column = index * CELL_WIDTH;
+            let width = labels.chars().count();
+
+            if labels.is_empty() || column > width {
+                let padding = column - width;
+                labels.push_str(&format!("{:padding$}{}", "", date.format("%b")));
+            }
+        }
+
+        labels
     }
 
     fn get_contribution_week(el: &ElementRef, selector: &Selector) -> Result<ContributionWeek, HeatmapError> {
@@ -100,25 +332,29 @@ impl Heatmap {
 mod tests {
     use super::*; 
 
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
     #[test]
     fn constructs_heatmap() {
         let fragment = Html::parse_fragment(r#"
             <svg class="js-calendar-graph-svg">
                 <g>
                     <g>
-                        <rect y='45' data-level='1' class="ContributionCalendar-day" />
-                        <rect y='60' data-level='2' class="ContributionCalendar-day" />
-                        <rect y='75' data-level='3' class="ContributionCalendar-day" />
-                        <rect y='90' data-level='4' class="ContributionCalendar-day" />
+                        <rect y='45' data-date='2022-09-14' data-level='1' class="ContributionCalendar-day">1 contribution on 2022-09-14</rect>
+                        <rect y='60' data-date='2022-09-15' data-level='2' class="ContributionCalendar-day">2 contributions on 2022-09-15</rect>
+                        <rect y='75' data-date='2022-09-16' data-level='3' class="ContributionCalendar-day">3 contributions on 2022-09-16</rect>
+                        <rect y='90' data-date='2022-09-17' data-level='4' class="ContributionCalendar-day">4 contributions on 2022-09-17</rect>
                     </g>
                     <g>
-                        <rect y='0' data-level='1' class="ContributionCalendar-day" />
-                        <rect y='15' data-level='2' class="ContributionCalendar-day" />
-                        <rect y='30' data-level='3' class="ContributionCalendar-day" />
-                        <rect y='45' data-level='4' class="ContributionCalendar-day" />
-                        <rect y='60' data-level='4' class="ContributionCalendar-day" />
-                        <rect y='75' data-level='4' class="ContributionCalendar-day" />
-                        <rect y='90' data-level='4' class="ContributionCalendar-day" />
+                        <rect y='0' data-date='2022-09-18' data-level='1' class="ContributionCalendar-day">1 contribution on 2022-09-18</rect>
+                        <rect y='15' data-date='2022-09-19' data-level='2' class="ContributionCalendar-day">2 contributions on 2022-09-19</rect>
+                        <rect y='30' data-date='2022-09-20' data-level='3' class="ContributionCalendar-day">3 contributions on 2022-09-20</rect>
+                        <rect y='45' data-date='2022-09-21' data-level='4' class="ContributionCalendar-day">4 contributions on 2022-09-21</rect>
+                        <rect y='60' data-date='2022-09-22' data-level='4' class="ContributionCalendar-day">4 contributions on 2022-09-22</rect>
+                        <rect y='75' data-date='2022-09-23' data-level='4' class="ContributionCalendar-day">4 contributions on 2022-09-23</rect>
+                        <rect y='90' data-date='2022-09-24' data-level='4' class="ContributionCalendar-day">4 contributions on 2022-09-24</rect>
                     </g>
                </g> 
             </svg>
@@ -132,21 +368,21 @@ mod tests {
                         None,
                         None,
                         None,
-                        Some(Contribution { heat_level: 1 }),
-                        Some(Contribution { heat_level: 2 }),
-                        Some(Contribution { heat_level: 3 }),
-                        Some(Contribution { heat_level: 4 }),
+                        Some(Contribution { date: date("2022-09-14"), heat_level: 1, count: 1 }),
+                        Some(Contribution { date: date("2022-09-15"), heat_level: 2, count: 2 }),
+                        Some(Contribution { date: date("2022-09-16"), heat_level: 3, count: 3 }),
+                        Some(Contribution { date: date("2022-09-17"), heat_level: 4, count: 4 }),
                     ]
                 },
                 ContributionWeek {
                     contributions: vec![
-                        Some(Contribution { heat_level: 1 }),
-                        Some(Contribution { heat_level: 2 }),
-                        Some(Contribution { heat_level: 3 }),
-                        Some(Contribution { heat_level: 4 }),
-                        Some(Contribution { heat_level: 4 }),
-                        Some(Contribution { heat_level: 4 }),
-                        Some(Contribution { heat_level: 4 }),
+                        Some(Contribution { date: date("2022-09-18"), heat_level: 1, count: 1 }),
+                        Some(Contribution { date: date("2022-09-19"), heat_level: 2, count: 2 }),
+                        Some(Contribution { date: date("2022-09-20"), heat_level: 3, count: 3 }),
+                        Some(Contribution { date: date("2022-09-21"), heat_level: 4, count: 4 }),
+                        Some(Contribution { date: date("2022-09-22"), heat_level: 4, count: 4 }),
+                        Some(Contribution { date: date("2022-09-23"), heat_level: 4, count: 4 }),
+                        Some(Contribution { date: date("2022-09-24"), heat_level: 4, count: 4 }),
                     ]
                 }
             ]
@@ -159,13 +395,13 @@ mod tests {
     fn gets_contribution_week() {
         let fragment = Html::parse_fragment(r#"
             <g>
-                <rect y='0' data-level='1' class="ContributionCalendar-day" />
-                <rect y='15' data-level='2' class="ContributionCalendar-day" />
-                <rect y='30' data-level='3' class="ContributionCalendar-day" />
-                <rect y='45' data-level='4' class="ContributionCalendar-day" />
-                <rect y='60' data-level='4' class="ContributionCalendar-day" />
-                <rect y='75' data-level='4' class="ContributionCalendar-day" />
-                <rect y='90' data-level='4' class="ContributionCalendar-day" />
+                <rect y='0' data-date='2022-09-18' data-level='1' class="ContributionCalendar-day">1 contribution on 2022-09-18</rect>
+                <rect y='15' data-date='2022-09-19' data-level='2' class="ContributionCalendar-day">2 contributions on 2022-09-19</rect>
+                <rect y='30' data-date='2022-09-20' data-level='3' class="ContributionCalendar-day">3 contributions on 2022-09-20</rect>
+                <rect y='45' data-date='2022-09-21' data-level='4' class="ContributionCalendar-day">4 contributions on 2022-09-21</rect>
+                <rect y='60' data-date='2022-09-22' data-level='4' class="ContributionCalendar-day">4 contributions on 2022-09-22</rect>
+                <rect y='75' data-date='2022-09-23' data-level='4' class="ContributionCalendar-day">4 contributions on 2022-09-23</rect>
+                <rect y='90' data-date='2022-09-24' data-level='4' class="ContributionCalendar-day">4 contributions on 2022-09-24</rect>
             </g>
         "#);
 
@@ -175,29 +411,194 @@ mod tests {
 
         let expected = ContributionWeek {
             contributions: vec![
-                Some(Contribution { heat_level: 1 }),
-                Some(Contribution { heat_level: 2 }),
-                Some(Contribution { heat_level: 3 }),
-                Some(Contribution { heat_level: 4 }),
-                Some(Contribution { heat_level: 4 }), 
-                Some(Contribution { heat_level: 4 }),
-                Some(Contribution { heat_level: 4 }),
+                Some(Contribution { date: date("2022-09-18"), heat_level: 1, count: 1 }),
+                Some(Contribution { date: date("2022-09-19"), heat_level: 2, count: 2 }),
+                Some(Contribution { date: date("2022-09-20"), heat_level: 3, count: 3 }),
+                Some(Contribution { date: date("2022-09-21"), heat_level: 4, count: 4 }),
+                Some(Contribution { date: date("2022-09-22"), heat_level: 4, count: 4 }), 
+                Some(Contribution { date: date("2022-09-23"), heat_level: 4, count: 4 }),
+                Some(Contribution { date: date("2022-09-24"), heat_level: 4, count: 4 }),
             ]
         };
 
         assert_eq!(contribution_week, expected) 
     }
 
+    #[test]
+    fn constructs_heatmap_from_contributions() {
+        let contribution = |value, count| Contribution { date: date(value), heat_level: 1, count };
+        let heatmap = Heatmap::from_contributions(vec![
+            contribution("2022-09-27", 2),
+            contribution("2022-09-23", 1),
+            contribution("2022-09-24", 0),
+        ]);
+
+        assert_eq!(heatmap.contribution_weeks, vec![
+            ContributionWeek {
+                contributions: vec![None, None, None, None, None, Some(contribution("2022-09-23", 1)), Some(contribution("2022-09-24", 0))]
+            },
+            ContributionWeek {
+                contributions: vec![None, None, Some(contribution("2022-09-27", 2)), None, None, None, None]
+            },
+        ]);
+        assert_eq!(Heatmap::from_contributions(vec![]).contribution_weeks, vec![]);
+    }
+
+    #[test]
+    fn iterates_days_skipping_empty_ones() {
+        let contribution = |value, count| Contribution { date: date(value), heat_level: 1, count };
+        let mut heatmap = Heatmap::from_contributions(vec![contribution("2022-09-17", 1), contribution("2022-09-18", 2)]);
+
+        heatmap.days_mut().for_each(|day| day.count *= 2);
+        let days: Vec<_> = heatmap.days().map(|day| (day.date.to_string(), day.count)).collect();
+
+        assert_eq!(heatmap.contribution_weeks.len(), 2);
+        assert_eq!(days, vec![("2022-09-17".to_string(), 2), ("2022-09-18".to_string(), 4)]);
+    }
+
+    #[test]
+    fn aligns_heatmap_to_dates() {
+        let contribution = |value| Contribution { date: date(value), heat_level: 1, count: 1 };
+        let heatmap = Heatmap::from_contributions(vec![contribution("2022-09-20"), contribution("2022-09-21")]);
+        let aligned = heatmap.aligned_to(date("2022-09-10"), date("2022-09-20"));
+
+        assert_eq!(heatmap.date_bounds(), Some((date("2022-09-20"), date("2022-09-21"))));
+        assert_eq!(aligned.contribution_weeks.len(), 3);
+        assert_eq!(aligned.date_bounds(), Some((date("2022-09-20"), date("2022-09-20"))));
+        assert_eq!(aligned.contribution_weeks[2].contributions[2], Some(contribution("2022-09-20")));
+    }
+
+    #[test]
+    fn gets_contribution_by_date() {
+        let heatmap = Heatmap {
+            contribution_weeks: vec![
+                ContributionWeek {
+                    contributions: vec![
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        Some(Contribution { date: date("2022-09-16"), heat_level: 3, count: 3 }),
+                        Some(Contribution { date: date("2022-09-17"), heat_level: 1, count: 1 }),
+                    ]
+                }
+            ]
+        };
+
+        assert_eq!(
+            heatmap.get_contribution(date("2022-09-16")),
+            Some(&Contribution { date: date("2022-09-16"), heat_level: 3, count: 3 })
+        );
+        assert_eq!(heatmap.get_contribution(date("2022-09-18")), None);
+    }
+
+    #[test]
+    fn finds_month_starts() {
+        let week = |first: &str, last: &str| ContributionWeek {
+            contributions: vec![
+                Some(Contribution { date: date(first), heat_level: 0, count: 0 }),
+                None, None, None, None, None,
+                Some(Contribution { date: date(last), heat_level: 0, count: 0 }),
+            ]
+        };
+        let heatmap = Heatmap {
+            contribution_weeks: vec![
+                week("2022-09-18", "2022-09-24"),
+                week("2022-09-25", "2022-10-01"),
+                week("2022-10-02", "2022-10-08"),
+            ]
+        };
+
+        assert_eq!(heatmap.month_starts(), vec![(0, date("2022-09-18")), (2, date("2022-10-02"))]);
+    }
+
+    #[test]
+    fn renders_heatmap_to_string() {
+        let first = Contribution { date: date("2022-09-17"), heat_level: 1, count: 1 };
+        let second = Contribution { date: date("2022-09-18"), heat_level: 4, count: 9 };
+        let heatmap = Heatmap {
+            contribution_weeks: vec![
+                ContributionWeek { contributions: vec![None, None, None, None, None, None, Some(first.clone())] },
+                ContributionWeek { contributions: vec![Some(second.clone()), None, None, None, None, None, None] },
+            ]
+        };
+
+        let palette = Palette::from(&crate::ColorValues::Green);
+        let rendered = heatmap.render_to_string(&palette, &GridOptions::plain());
+        let rows: Vec<_> = rendered.lines().collect();
+
+        assert_eq!(rows.len(), DAYS_IN_WEEK);
+        assert_eq!(rows[0], format!("  {}", second.render(&palette)));
+        assert_eq!(rows[1], "    ");
+        assert_eq!(rows[6], format!("{}  ", first.render(&palette)));
+    }
+
+    #[test]
+    fn renders_heatmap_with_glyph_ramp() {
+        let first = Contribution { date: date("2022-09-17"), heat_level: 1, count: 1 };
+        let second = Contribution { date: date("2022-09-18"), heat_level: 4, count: 9 };
+        let heatmap = Heatmap {
+            contribution_weeks: vec![
+                ContributionWeek { contributions: vec![None, None, None, None, None, None, Some(first)] },
+                ContributionWeek { contributions: vec![Some(second), None, None, None, None, None, None] },
+            ]
+        };
+
+        let ramp = GlyphRamp::new(" ░▒▓█").unwrap();
+        let rendered = heatmap.render_to_string(&ramp, &GridOptions::plain());
+
+        assert_eq!(rendered, "  █ \n    \n    \n    \n    \n    \n░   \n");
+    }
+
+    #[test]
+    fn renders_labels_and_legend() {
+        let weeks = ["2022-09-18", "2022-09-25", "2022-10-02", "2022-10-09", "2022-10-16", "2022-10-23", "2022-10-30", "2022-11-06"]
+            .map(|sunday| {
+                let sunday = date(sunday);
+                let contributions = (0..7)
+                    .map(|offset| Some(Contribution { date: sunday + chrono::Duration::days(offset), heat_level: 0, count: 0 }))
+                    .collect();
+
+                ContributionWeek { contributions }
+            });
+        let heatmap = Heatmap { contribution_weeks: weeks.into_iter().collect() };
+
+        let ramp = GlyphRamp::new("·░▒▓█").unwrap();
+        let rendered = heatmap.render_to_string(&ramp, &GridOptions::default());
+        let rows: Vec<_> = rendered.lines().collect();
+
+        assert_eq!(rows.len(), DAYS_IN_WEEK + 2);
+        assert_eq!(rows[0], "    Sep Oct       Nov");
+        assert_eq!(rows[1], "    · · · · · · · · ");
+        assert_eq!(rows[2], "Mon · · · · · · · · ");
+        assert_eq!(rows[4], "Wed · · · · · · · · ");
+        assert_eq!(rows[6], "Fri · · · · · · · · ");
+        assert_eq!(rows[8], " Less · ░ ▒ ▓ █ More");
+    }
+
+    #[test]
+    fn skips_overlapping_month_labels() {
+        let week = |sunday: &str| ContributionWeek {
+            contributions: vec![Some(Contribution { date: date(sunday), heat_level: 0, count: 0 }), None, None, None, None, None, None]
+        };
+        let heatmap = Heatmap {
+            contribution_weeks: vec![week("2022-09-25"), week("2022-10-02"), week("2022-10-09"), week("2022-10-16")]
+        };
+
+        assert_eq!(heatmap.render_month_labels(), "Sep");
+    }
+
     #[test]
     fn error_if_cannot_parse_contribution_week() {
         let fragment = Html::parse_fragment(r#"
-            <rect y='0' data-level='1' class="InvalidClass" />
-            <rect y='15' data-level='2' class="InvalidClass" />
-            <rect y='30' data-level='3' class="InvalidClass" />
-            <rect y='45' data-level='4' class="InvalidClass" />
-            <rect y='60' data-level='4' class="InvalidClass" />
-            <rect y='75' data-level='4' class="InvalidClass" />
-            <rect y='90' data-level='4' class="InvalidClass" />
+            <rect y='0' data-date='2022-09-18' data-level='1' class="InvalidClass">1 contribution on 2022-09-18</rect>
+            <rect y='15' data-date='2022-09-19' data-level='2' class="InvalidClass">2 contributions on 2022-09-19</rect>
+            <rect y='30' data-date='2022-09-20' data-level='3' class="InvalidClass">3 contributions on 2022-09-20</rect>
+            <rect y='45' data-date='2022-09-21' data-level='4' class="InvalidClass">4 contributions on 2022-09-21</rect>
+            <rect y='60' data-date='2022-09-22' data-level='4' class="InvalidClass">4 contributions on 2022-09-22</rect>
+            <rect y='75' data-date='2022-09-23' data-level='4' class="InvalidClass">4 contributions on 2022-09-23</rect>
+            <rect y='90' data-date='2022-09-24' data-level='4' class="InvalidClass">4 contributions on 2022-09-24</rect>
         "#);
 
         let el = fragment.root_element();
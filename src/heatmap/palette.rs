@@ -0,0 +1,139 @@
+use crate::ColorValues;
+use super::{CellRenderer, ColorDepth, HEAT_LEVELS};
+
+/// A `Palette` maps each heat level of a [`super::Contribution`] to an RGB
+/// fill color, from level 0 (no contributions) to level 4.
+///
+/// Palettes are constructed from a [`ColorValues`] variant, a named preset
+/// (see [`Palette::preset`]) or a list of hexadecimal colors (see
+/// [`Palette::from_hex_list`]), and are shared by every output format. Terminal
+/// output additionally quantizes the colors to the palette's [`ColorDepth`].
+///
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Palette {
+    /// RGB fill colors, indexed by heat level.
+    pub stops: [(u8, u8, u8); HEAT_LEVELS],
+    /// Color depth used when painting terminal output.
+    pub depth: ColorDepth,
+}
+
+/// Names of the presets accepted by [`Palette::preset`].
+pub const PALETTE_PRESETS: [&str; 4] = ["github-light", "github-dark", "halloween", "viridis"];
+
+impl Palette {
+    /// Returns the RGB fill color used to shade a heatmap node of the provided
+    /// heat level. Levels above 4 are shaded like level 4.
+    pub fn rgb(&self, heat_level: usize) -> (u8, u8, u8) {
+        self.stops[heat_level.min(HEAT_LEVELS - 1)]
+    }
+
+    /// Returns a copy of the palette which paints terminal output with the
+    /// provided color depth.
+    pub fn with_depth(mut self, depth: ColorDepth) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Paints the provided text with the fill color of the provided heat
+    /// level, quantized to the palette's [`ColorDepth`].
+    pub fn paint(&self, text: &str, heat_level: usize) -> String {
+        self.depth.paint(text, self.rgb(heat_level), heat_level)
+    }
+
+    /// Looks up a named preset palette. See [`PALETTE_PRESETS`] for the
+    /// supported names.
+    pub fn preset(name: &str) -> Option<Self> {
+        let stops = match name {
+            "github-light" => ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"],
+            "github-dark" => ["#161b22", "#0e4429", "#006d32", "#26a641", "#39d353"],
+            "halloween" => ["#ebedf0", "#ffee4a", "#ffc501", "#fe9600", "#03001c"],
+            "viridis" => ["#440154", "#3b528b", "#21918c", "#5ec962", "#fde725"],
+            _ => return None,
+        };
+
+        Self::from_hex_list(&stops.join(",")).ok()
+    }
+
+    /// Constructs a new `Palette` instance from a comma-separated list of five
+    /// hexadecimal colors, e.g. `#ebedf0,#9be9a8,#40c463,#30a14e,#216e39`.
+    ///
+    /// # Errors
+    /// Returns an error if the list does not contain exactly five valid
+    /// hexadecimal colors.
+    ///
+    pub fn from_hex_list(value: &str) -> Result<Self, String> {
+        let colors = value
+            .split(',')
+            .map(crate::parse_hex_color)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let stops = colors
+            .try_into()
+            .map_err(|colors: Vec<_>| format!("palette must contain {HEAT_LEVELS} colors, found {}", colors.len()))?;
+
+        Ok(Palette { stops, depth: ColorDepth::default() })
+    }
+}
+
+impl CellRenderer for Palette {
+    fn render_level(&self, heat_level: usize) -> String {
+        self.paint("\u{025A0} ", heat_level)
+    }
+}
+
+impl From<&ColorValues> for Palette {
+    /// Scales the channel of the color variant linearly with the heat level.
+    fn from(color: &ColorValues) -> Self {
+        let stops = [0, 64, 127, 191, 255].map(|intensity| match color {
+            ColorValues::Red => (intensity, 0, 0),
+            ColorValues::Green => (0, intensity, 0),
+            ColorValues::Blue => (0, 0, intensity),
+        });
+
+        Palette { stops, depth: ColorDepth::default() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constructs_palette_from_color_values() {
+        let palette = Palette::from(&ColorValues::Blue);
+
+        assert_eq!(palette.rgb(0), (0, 0, 0));
+        assert_eq!(palette.rgb(2), (0, 0, 127));
+        assert_eq!(palette.rgb(9), (0, 0, 255));
+    }
+
+    #[test]
+    fn paints_with_palette_depth() {
+        let palette = Palette::from(&ColorValues::Green).with_depth(ColorDepth::Monochrome);
+
+        assert_eq!(palette.depth, ColorDepth::Monochrome);
+        assert_eq!(palette.paint("\u{025A0} ", 2), "▒ ");
+    }
+
+    #[test]
+    fn constructs_palette_from_hex_list() {
+        let palette = Palette::from_hex_list("#000000,#111111,#222222,#333333,#ffffff").unwrap();
+        assert_eq!(palette.stops, [(0, 0, 0), (17, 17, 17), (34, 34, 34), (51, 51, 51), (255, 255, 255)]);
+    }
+
+    #[test]
+    fn error_if_wrong_number_of_colors() {
+        let result = Palette::from_hex_list("#000000,#111111");
+        assert_eq!(result, Err("palette must contain 5 colors, found 2".to_string()));
+    }
+
+    #[test]
+    fn looks_up_presets() {
+        for name in PALETTE_PRESETS {
+            assert!(Palette::preset(name).is_some(), "missing preset {name}");
+        }
+
+        assert_eq!(Palette::preset("github-light").unwrap().rgb(4), (0x21, 0x6e, 0x39));
+        assert_eq!(Palette::preset("sepia"), None);
+    }
+}
@@ -1,37 +1,736 @@
+mod formats;
 mod heatmap;
+mod sources;
+mod stats;
 mod utils;
 
-use std::error;
-use reqwest::StatusCode;
-use scraper::Html;
-pub use heatmap::{Heatmap, ContributionWeek, Contribution};
-pub use utils::args::{Args, ColorValues};
-pub use utils::errors::{HeatmapError, GithubError};
-pub use utils::parsers::{parse_slug, parse_year};
+use std::{error, fs::File, io::{self, BufWriter, Write}, path::Path, thread, time::Duration};
+use sources::read_input;
+pub use formats::{write_csv, write_html, write_json, write_png, write_svg, write_tsv, PngOptions};
+pub use heatmap::{Heatmap, ContributionWeek, Contribution, CellRenderer, ColorDepth, GlyphRamp, GridOptions, HeatmapDiff, Palette, PALETTE_PRESETS};
+pub use sources::{
+    CacheEntry, ContributionSource, DateRange, FileSource, GraphQlSource, ProfileScraper,
+    ResponseCache, RetryPolicy
+};
+pub use stats::{Stats, Streak};
+pub use utils::args::{Args, INPUT_LABEL, ColorModeValues, ColorValues, ErrorFormatValues, FormatValues, LayoutValues, SourceValues};
+pub use utils::errors::{ArgumentError, CompareError, ErrorCategory, ErrorReport, HeatmapError, GithubError, InputError, OutputError};
+pub use utils::parsers::{parse_date, parse_glyph_ramp, parse_hex_color, parse_palette, parse_slug, parse_slug_list, parse_year};
 
-const PROFILE_URL: &str = "https://github.com";
+pub fn run(args: &Args) -> Result<(), Box<dyn error::Error + Send + Sync>> {
+    if let Some(input) = &args.input {
+        return run_with_source(args, &FileSource::new(input));
+    }
 
-pub fn run(args: &Args) -> Result<(), Box<dyn error::Error>> {
-    let profile_url = match &args.year {
-        Some(year) => format!("{PROFILE_URL}/{slug}?from={year}-01-01", slug = args.slug),
-        None => format!("{PROFILE_URL}/{slug}", slug = args.slug)
+    let retry_policy = RetryPolicy {
+        max_retries: args.retries,
+        base_delay: Duration::from_millis(args.retry_delay),
+        ..Default::default()
     };
 
-    let profile = get_github_profile(&profile_url)?;
-    let heatmap = Heatmap::from_document(&profile)?;
-    heatmap.render(&args.color);
+    match args.source {
+        SourceValues::Html => {
+            let mut source = ProfileScraper::new().with_retry_policy(retry_policy);
+
+            if let Some(dir) = ResponseCache::default_dir().filter(|_| !args.no_cache) {
+                let cache = ResponseCache::new(dir, Duration::from_secs(args.cache_ttl));
+                source = source.with_cache(cache.with_refresh(args.refresh));
+            }
+
+            run_with_source(args, &source)
+        },
+        SourceValues::Graphql => {
+            let token = args.token.as_deref().ok_or(GithubError::MissingToken)?;
+            let source = GraphQlSource::new(token).with_retry_policy(retry_policy);
+            run_with_source(args, &source)
+        }
+    }
+}
+
+/// Fetches a [`Heatmap`] from the provided [`ContributionSource`] according to
+/// the provided CLI arguments, and writes it to standard output in the requested
+/// format.
+///
+/// If several profile slugs are provided, their heatmaps are fetched concurrently
+/// and drawn beneath each other on the same date axis, or summed into a single
+/// team heatmap (see [`Heatmap::sum`]) if requested. With `--diff`, two
+/// profiles, or one profile in two years, are drawn as a [`HeatmapDiff`].
+///
+/// # Errors
+/// Returns an error if the requested date range or diff is invalid, or if the
+/// [`ContributionSource`] fails to produce a [`Heatmap`]. When comparing several
+/// profiles, a [`CompareError`] is returned after writing the heatmaps of the
+/// remaining profiles. A team heatmap is only written if every member's heatmap
+/// could be fetched.
+///
+pub fn run_with_source(
+    args: &Args,
+    source: &(dyn ContributionSource + Sync)
+) -> Result<(), Box<dyn error::Error + Send + Sync>> {
+    args.validate()?;
+
+    // Output files are never colorized automatically, so heat levels are shaded with glyphs.
+    let depth = match (&args.color_mode, &args.output) {
+        (ColorModeValues::Auto, Some(_)) => ColorDepth::Monochrome,
+        (mode, _) => ColorDepth::from(mode),
+    };
+
+    let palette = args.palette
+        .clone()
+        .unwrap_or_else(|| Palette::from(&args.color))
+        .with_depth(depth);
+
+    // An explicit color mode is honoured even if standard output is not a terminal.
+    if !matches!(args.color_mode, ColorModeValues::Auto | ColorModeValues::None) {
+        colored::control::set_override(true);
+    }
+
+    let mut writer: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path).map_err(|source| OutputError::Create {
+            path: path.display().to_string(),
+            source
+        })?)),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    let slugs = match &args.team_file {
+        Some(path) => [args.slugs.clone(), read_team_file(path)?].concat(),
+        None => args.profile_slugs(),
+    };
+
+    let result = match (args.team || args.team_file.is_some(), slugs.as_slice()) {
+        (false, slugs) if args.diff => write_diff(args, slugs, source, &palette, &mut writer),
+        (true, slugs) => write_team(args, slugs, source, &palette, &mut writer),
+        (false, [slug]) => write_profile(args, slug, source, &palette, &mut writer),
+        (false, slugs) => write_comparison(args, slugs, source, &palette, &mut writer),
+    };
+
+    writer.flush().map_err(OutputError::Write)?;
+
+    result
+}
+
+fn write_profile(
+    args: &Args,
+    slug: &str,
+    source: &dyn ContributionSource,
+    palette: &Palette,
+    writer: &mut impl Write
+) -> Result<(), Box<dyn error::Error + Send + Sync>> {
+    let years = match args.all_years {
+        true => Some(source.contribution_years(slug)?),
+        false => args.years(),
+    };
+
+    let stacked = matches!((&args.format, &args.layout), (FormatValues::Grid, LayoutValues::Stacked));
+
+    match years {
+        Some(years) if stacked && (args.all_years || years.len() > 1) => {
+            for (index, year) in years.iter().enumerate() {
+                let heatmap = source.fetch_range(slug, &DateRange::from_year(*year))?;
+
+                if index > 0 {
+                    writeln!(writer).map_err(OutputError::Write)?;
+                }
+                writeln!(writer, "{year}").map_err(OutputError::Write)?;
+                write_grid(args, &heatmap, palette, writer)?;
+            }
+        },
+        years => {
+            let range = match years {
+                Some(years) => DateRange::spanning_years(&years),
+                None => args.date_range()?,
+            };
+
+            let heatmap = match range {
+                Some(range) => source.fetch_range(slug, &range)?,
+                None => source.fetch_heatmap(slug, None)?,
+            };
+
+            write_heatmap(args, slug, &heatmap, palette, writer)?;
+        },
+    }
+
+    Ok(())
+}
+
+fn write_comparison(
+    args: &Args,
+    slugs: &[String],
+    source: &(dyn ContributionSource + Sync),
+    palette: &Palette,
+    writer: &mut impl Write
+) -> Result<(), Box<dyn error::Error + Send + Sync>> {
+    if !matches!(args.format, FormatValues::Grid) {
+        return Err(OutputError::Unsupported("comparing several profiles requires the grid format".to_string()).into());
+    }
+
+    let (fetched, error) = partition_heatmaps(slugs, fetch_profiles(args, slugs, source)?);
+    let (fetched, heatmaps): (Vec<_>, Vec<_>) = fetched.into_iter().unzip();
+
+    for (index, (slug, heatmap)) in fetched.iter().zip(align_heatmaps(heatmaps)).enumerate() {
+        if index > 0 {
+            writeln!(writer).map_err(OutputError::Write)?;
+        }
+        writeln!(writer, "{slug}").map_err(OutputError::Write)?;
+        write_grid(args, &heatmap, palette, writer)?;
+    }
+
+    match error {
+        Some(error) => Err(error.into()),
+        None => Ok(()),
+    }
+}
+
+fn write_diff(
+    args: &Args,
+    slugs: &[String],
+    source: &(dyn ContributionSource + Sync),
+    palette: &Palette,
+    writer: &mut impl Write
+) -> Result<(), Box<dyn error::Error + Send + Sync>> {
+    if !matches!(args.format, FormatValues::Grid) {
+        return Err(OutputError::Unsupported("diffing heatmaps requires the grid format".to_string()).into());
+    }
+
+    let diff = match (slugs, args.years().as_deref()) {
+        ([left, right], _) => {
+            // Both heatmaps span the same dates, so that each day is compared to itself.
+            let heatmaps = collect_heatmaps(slugs, fetch_profiles(args, slugs, source)?)?;
+            let mut heatmaps = align_heatmaps(heatmaps);
+
+            let right_heatmap = heatmaps.pop().unwrap();
+            HeatmapDiff::new(heatmaps.pop().unwrap(), right_heatmap).with_labels(left, right)
+        },
+        ([slug], Some(&[left, right])) => {
+            let left_heatmap = source.fetch_range(slug, &DateRange::from_year(left))?;
+            let right_heatmap = source.fetch_range(slug, &DateRange::from_year(right))?;
+
+            HeatmapDiff::new(left_heatmap, right_heatmap).with_labels(&left.to_string(), &right.to_string())
+        },
+        _ => return Err(ArgumentError::DiffOperands.into()),
+    };
+
+    diff.render_to(writer, palette.depth, &grid_options(args)).map_err(OutputError::Write)?;
+    writeln!(writer).map_err(OutputError::Write)?;
+    diff.render_summary_to(writer).map_err(OutputError::Write)?;
+
+    Ok(())
+}
+
+fn write_team(
+    args: &Args,
+    slugs: &[String],
+    source: &(dyn ContributionSource + Sync),
+    palette: &Palette,
+    writer: &mut impl Write
+) -> Result<(), Box<dyn error::Error + Send + Sync>> {
+    let heatmaps = collect_heatmaps(slugs, fetch_profiles(args, slugs, source)?)?;
+
+    write_heatmap(args, &slugs.join(", "), &Heatmap::sum(&heatmaps), palette, writer)?;
 
     Ok(())
 }
 
-fn get_github_profile(profile_url: &str) -> Result<Html, GithubError> {
-    let response = reqwest::blocking::get(profile_url).unwrap();
+/// Splits the results of [`fetch_profiles`] into the heatmaps which could be
+/// fetched, alongside their slugs, and a [`CompareError`] naming the profiles
+/// which could not be fetched, if any.
+fn partition_heatmaps(slugs: &[String], results: Vec<FetchResult>) -> (Vec<(&str, Heatmap)>, Option<CompareError>) {
+    let mut heatmaps = vec![];
+    let mut failed = vec![];
+    let mut first_error = None;
+
+    for (slug, result) in slugs.iter().zip(results) {
+        match result {
+            Ok(heatmap) => heatmaps.push((slug.as_str(), heatmap)),
+            Err(e) => {
+                failed.push(slug.clone());
+                first_error.get_or_insert(e);
+            },
+        }
+    }
+
+    (heatmaps, first_error.map(|source| CompareError::Incomplete { slugs: failed, source }))
+}
+
+/// Collects the results of [`fetch_profiles`] if every profile could be fetched.
+fn collect_heatmaps(slugs: &[String], results: Vec<FetchResult>) -> Result<Vec<Heatmap>, CompareError> {
+    match partition_heatmaps(slugs, results) {
+        (heatmaps, None) => Ok(heatmaps.into_iter().map(|(_, heatmap)| heatmap).collect()),
+        (_, Some(error)) => Err(error),
+    }
+}
+
+/// Extends every heatmap to span the dates of all provided heatmaps, so that
+/// their columns line up.
+fn align_heatmaps(heatmaps: Vec<Heatmap>) -> Vec<Heatmap> {
+    let bounds = heatmaps
+        .iter()
+        .filter_map(Heatmap::date_bounds)
+        .reduce(|(from, to), (other_from, other_to)| (from.min(other_from), to.max(other_to)));
+
+    match bounds {
+        Some((from, to)) => heatmaps.into_iter().map(|heatmap| heatmap.aligned_to(from, to)).collect(),
+        None => heatmaps,
+    }
+}
+
+fn read_team_file(path: &Path) -> Result<Vec<String>, InputError> {
+    parse_slug_list(&read_input(path)?).map_err(|message| InputError::InvalidSlug {
+        path: path.display().to_string(),
+        message
+    })
+}
+
+/// Outcome of fetching the heatmap of a single profile among several.
+type FetchResult = Result<Heatmap, Box<dyn error::Error + Send + Sync>>;
+
+/// Fetches the heatmaps of several profiles concurrently, returning the result
+/// for each profile in the order of the provided slugs.
+fn fetch_profiles(
+    args: &Args,
+    slugs: &[String],
+    source: &(dyn ContributionSource + Sync)
+) -> Result<Vec<FetchResult>, ArgumentError> {
+    let range = args.date_range()?;
+
+    Ok(thread::scope(|scope| {
+        let handles: Vec<_> = slugs
+            .iter()
+            .map(|slug| scope.spawn(move || fetch_profile(args, slug, source, range)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("fetching thread panicked"))
+            .collect()
+    }))
+}
+
+fn fetch_profile(
+    args: &Args,
+    slug: &str,
+    source: &dyn ContributionSource,
+    range: Option<DateRange>
+) -> Result<Heatmap, Box<dyn error::Error + Send + Sync>> {
+    let range = match args.all_years {
+        true => DateRange::spanning_years(&source.contribution_years(slug)?),
+        false => range,
+    };
+
+    match range {
+        Some(range) => source.fetch_range(slug, &range),
+        None => source.fetch_heatmap(slug, None),
+    }
+}
+
+fn write_heatmap(
+    args: &Args,
+    slug: &str,
+    heatmap: &Heatmap,
+    palette: &Palette,
+    writer: &mut impl Write
+) -> Result<(), OutputError> {
+    match args.format {
+        FormatValues::Grid => write_grid(args, heatmap, palette, writer),
+        FormatValues::Json => write_json(heatmap, writer),
+        FormatValues::Csv => write_csv(heatmap, writer),
+        FormatValues::Tsv => write_tsv(heatmap, writer),
+        FormatValues::Svg => write_svg(heatmap, writer, palette, args.background),
+        FormatValues::Png => {
+            let options = PngOptions {
+                cell_size: args.cell_size,
+                gap: args.cell_gap,
+                padding: args.padding,
+                background: args.background,
+            };
+            write_png(heatmap, writer, palette, &options)
+        },
+        FormatValues::Html => write_html(heatmap, writer, palette, slug, args.background),
+    }
+}
+
+fn grid_options(args: &Args) -> GridOptions {
+    GridOptions {
+        month_labels: !args.no_month_labels,
+        weekday_labels: !args.no_weekday_labels,
+        legend: !args.no_legend,
+    }
+}
+
+fn write_grid(
+    args: &Args,
+    heatmap: &Heatmap,
+    palette: &Palette,
+    writer: &mut impl Write
+) -> Result<(), OutputError> {
+    let options = grid_options(args);
+
+    match &args.glyphs {
+        Some(ramp) => heatmap.render_to(writer, ramp, &options),
+        None => heatmap.render_to(writer, palette, &options),
+    }.map_err(OutputError::Write)?;
+
+    if args.stats {
+        writeln!(writer).map_err(OutputError::Write)?;
+        Stats::from_heatmap(heatmap).render_to(writer).map_err(OutputError::Write)?
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, sync::Mutex};
+    use chrono::NaiveDate;
+    use clap::Parser;
+
+    /// Records requested ranges, and produces one contribution for every day
+    /// of the requested range. Profiles named `nobody` do not exist.
+    struct StubSource {
+        requested: Mutex<Vec<(String, Option<DateRange>)>>,
+    }
+
+    impl StubSource {
+        fn new() -> Self {
+            StubSource { requested: Mutex::new(vec![]) }
+        }
+    }
+
+    impl ContributionSource for StubSource {
+        fn fetch_heatmap(
+            &self,
+            slug: &str,
+            range: Option<&DateRange>
+        ) -> Result<Heatmap, Box<dyn error::Error + Send + Sync>> {
+            self.requested.lock().unwrap().push((slug.to_string(), range.copied()));
+
+            if slug == "nobody" {
+                return Err(GithubError::ProfileNotFound(format!("https://github.com/{slug}")).into());
+            }
+
+            let contributions = range
+                .map(|range| range.from
+                    .iter_days()
+                    .take_while(|date| *date <= range.to)
+                    .map(|date| Contribution { date, heat_level: 1, count: 1 })
+                    .collect())
+                .unwrap_or_default();
+
+            Ok(Heatmap::from_contributions(contributions))
+        }
+    }
+
+    fn args(output: Option<std::path::PathBuf>) -> Args {
+        Args {
+            slugs: vec!["torvalds".to_string()],
+            team: false,
+            team_file: None,
+            diff: false,
+            color: ColorValues::Green,
+            palette: None,
+            color_mode: ColorModeValues::Auto,
+            glyphs: None,
+            format: FormatValues::Grid,
+            output,
+            no_month_labels: false,
+            no_weekday_labels: false,
+            no_legend: false,
+            stats: false,
+            cell_size: 10,
+            cell_gap: 3,
+            padding: 10,
+            background: (0, 0, 0),
+            year: Some(vec![2022..=2022]),
+            all_years: false,
+            layout: LayoutValues::Stacked,
+            from: None,
+            to: None,
+            source: SourceValues::Html,
+            token: None,
+            input: None,
+            retries: 0,
+            retry_delay: 0,
+            cache_ttl: 0,
+            no_cache: true,
+            refresh: false,
+            error_format: ErrorFormatValues::Text,
+        }
+    }
+
+    #[test]
+    fn runs_with_custom_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = StubSource::new();
+
+        run_with_source(&args(Some(dir.path().join("heatmap.txt"))), &source).unwrap();
+
+        assert_eq!(
+            source.requested.into_inner().unwrap(),
+            vec![("torvalds".to_string(), Some(DateRange::from_year(2022)))]
+        )
+    }
+
+    #[test]
+    fn stacks_grids_for_multiple_years() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("heatmap.txt");
+        let source = StubSource::new();
+        let args = Args { year: Some(vec![2020..=2021]), ..args(Some(path.clone())) };
+
+        run_with_source(&args, &source).unwrap();
+        let output = fs::read_to_string(path).unwrap();
+
+        assert!(output.starts_with("2020\n"));
+        assert!(output.contains("More\n\n2021\n"));
+        assert_eq!(source.requested.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn shades_output_files_with_glyphs_in_auto_color_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("heatmap.txt");
+
+        run_with_source(&args(Some(path.clone())), &StubSource::new()).unwrap();
+        let output = fs::read_to_string(path).unwrap();
+
+        assert!(output.contains("Less · ░ ▒ ▓ █ More"));
+        assert!(!output.contains('\u{025A0}') && !output.contains('\x1b'));
+    }
+
+    #[test]
+    fn labels_input_without_slug() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("heatmap.html");
+        let source = StubSource::new();
+
+        assert!(Args::try_parse_from(["github-heatmap", "-f", "html"]).is_err());
+        let args = Args::try_parse_from(["github-heatmap", "--input", "page.html", "-f", "html", "-y", "2022"]).unwrap();
+        let args = Args { output: Some(path.clone()), no_cache: true, ..args };
+
+        run_with_source(&args, &source).unwrap();
+
+        assert!(fs::read_to_string(path).unwrap().contains("<title>Github contributions: input</title>"));
+        assert_eq!(source.requested.into_inner().unwrap()[0].0, INPUT_LABEL);
+    }
+
+    #[test]
+    fn stacks_only_listed_years() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = StubSource::new();
+        let args = Args::try_parse_from(["github-heatmap", "torvalds", "--years", "2019,2021..2022"]).unwrap();
+        let args = Args { output: Some(dir.path().join("heatmap.txt")), no_cache: true, ..args };
+
+        assert_eq!(args.years(), Some(vec![2019, 2021, 2022]));
+        assert_eq!(args.validate(), Ok(()));
+
+        run_with_source(&args, &source).unwrap();
+
+        assert_eq!(
+            source.requested.into_inner().unwrap(),
+            [2019, 2021, 2022].map(|year| ("torvalds".to_string(), Some(DateRange::from_year(year)))).to_vec()
+        );
+    }
+
+    #[test]
+    fn error_if_cell_size_is_zero() {
+        assert!(Args::try_parse_from(["github-heatmap", "torvalds", "--cell-size", "0"]).is_err());
+        assert!(Args::try_parse_from(["github-heatmap", "torvalds", "--cell-size", "1"]).is_ok());
+    }
+
+    #[test]
+    fn error_if_years_have_gaps_in_single_range() {
+        let years = || Some(vec![2019..=2019, 2021..=2021]);
+        let timeline = Args { year: years(), layout: LayoutValues::Timeline, ..args(None) };
+        let compare = Args { year: years(), slugs: vec!["alice".to_string(), "bob".to_string()], ..args(None) };
+        let diff = Args { year: years(), diff: true, ..args(None) };
+
+        assert_eq!(timeline.validate(), Err(ArgumentError::NonConsecutiveYears));
+        assert_eq!(compare.validate(), Err(ArgumentError::NonConsecutiveYears));
+        assert_eq!(diff.validate(), Ok(()));
+    }
+
+    #[test]
+    fn draws_timeline_for_multiple_years() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("heatmap.csv");
+        let source = StubSource::new();
+        let args = Args {
+            year: Some(vec![2020..=2021]),
+            layout: LayoutValues::Timeline,
+            format: FormatValues::Csv,
+            ..args(Some(path.clone()))
+        };
+
+        run_with_source(&args, &source).unwrap();
+        let output = fs::read_to_string(path).unwrap();
+
+        assert_eq!(output.lines().count(), 1 + 366 + 365);
+        assert_eq!(
+            source.requested.into_inner().unwrap(),
+            vec![
+                ("torvalds".to_string(), Some(DateRange::from_year(2020))),
+                ("torvalds".to_string(), Some(DateRange::from_year(2021))),
+            ]
+        );
+    }
+
+    #[test]
+    fn compares_profiles_on_same_date_axis() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("heatmap.txt");
+        let source = StubSource::new();
+        let args = Args {
+            slugs: vec!["alice".to_string(), "bob".to_string()],
+            year: None,
+            from: NaiveDate::from_ymd_opt(2022, 9, 14),
+            to: NaiveDate::from_ymd_opt(2022, 9, 20),
+            no_legend: true,
+            glyphs: Some(GlyphRamp::new(" ░▒▓█").unwrap()),
+            ..args(Some(path.clone()))
+        };
+
+        run_with_source(&args, &source).unwrap();
+        let output = fs::read_to_string(path).unwrap();
+        let grids: Vec<_> = output.split("\n\n").collect();
+
+        assert_eq!(grids.len(), 2);
+        assert!(grids[0].starts_with("alice\n"));
+        assert!(grids[1].starts_with("bob\n"));
+        assert_eq!(format!("{}\n", grids[0].replacen("alice", "bob", 1)), grids[1]);
+    }
+
+    #[test]
+    fn reports_failed_profiles_without_stopping_others() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("heatmap.txt");
+        let source = StubSource::new();
+        let args = Args {
+            slugs: vec!["nobody".to_string(), "torvalds".to_string()],
+            ..args(Some(path.clone()))
+        };
+
+        let error = run_with_source(&args, &source).unwrap_err();
+        let output = fs::read_to_string(path).unwrap();
+
+        assert_eq!(ErrorReport::new(error.as_ref()).exit_code(), 3);
+        assert!(error.to_string().starts_with("Failed to fetch contributions for nobody: Unable to find Github profile"));
+        assert!(output.starts_with("torvalds\n    Jan"));
+        assert!(!output.contains("nobody"));
+    }
+
+    #[test]
+    fn error_if_comparing_in_other_formats() {
+        let args = Args {
+            slugs: vec!["alice".to_string(), "bob".to_string()],
+            format: FormatValues::Json,
+            ..args(None)
+        };
+
+        let error = run_with_source(&args, &StubSource::new()).unwrap_err();
+        assert!(matches!(error.downcast_ref::<OutputError>(), Some(OutputError::Unsupported(_))));
+    }
+
+    #[test]
+    fn sums_team_members_from_slugs_and_team_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("heatmap.csv");
+        let team_file = dir.path().join("team.txt");
+        fs::write(&team_file, "# backend\nbob\ncarol\n").unwrap();
+
+        let source = StubSource::new();
+        let args = Args {
+            slugs: vec!["alice".to_string()],
+            team_file: Some(team_file),
+            format: FormatValues::Csv,
+            ..args(Some(path.clone()))
+        };
+
+        run_with_source(&args, &source).unwrap();
+        let output = fs::read_to_string(path).unwrap();
+
+        assert_eq!(output.lines().nth(1), Some("2022-01-01,Sat,0,1,3"));
+        assert_eq!(output.lines().count(), 1 + 365);
+        assert_eq!(source.requested.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn error_if_team_member_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("heatmap.csv");
+        let args = Args {
+            slugs: vec!["alice".to_string(), "nobody".to_string()],
+            team: true,
+            format: FormatValues::Csv,
+            ..args(Some(path.clone()))
+        };
+
+        let error = run_with_source(&args, &StubSource::new()).unwrap_err();
+
+        assert!(matches!(error.downcast_ref::<CompareError>(), Some(CompareError::Incomplete { slugs, .. }) if slugs == &["nobody"]));
+        assert_eq!(fs::read_to_string(path).unwrap(), "");
+    }
+
+    #[test]
+    fn error_if_team_file_contains_invalid_slug() {
+        let dir = tempfile::tempdir().unwrap();
+        let team_file = dir.path().join("team.txt");
+        fs::write(&team_file, "alice\nbob smith\n").unwrap();
+
+        let args = Args { slugs: vec![], team_file: Some(team_file), ..args(None) };
+        let error = run_with_source(&args, &StubSource::new()).unwrap_err();
+
+        assert!(matches!(error.downcast_ref::<InputError>(), Some(InputError::InvalidSlug { .. })));
+    }
+
+    #[test]
+    fn diffs_one_profile_over_two_years() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("diff.txt");
+        let source = StubSource::new();
+        let args = Args {
+            diff: true,
+            year: Some(vec![2022..=2022, 2023..=2023]),
+            color_mode: ColorModeValues::None,
+            ..args(Some(path.clone()))
+        };
+
+        run_with_source(&args, &source).unwrap();
+        let output = fs::read_to_string(path).unwrap();
+
+        assert_eq!(source.requested.into_inner().unwrap(), vec![
+            ("torvalds".to_string(), Some(DateRange::from_year(2022))),
+            ("torvalds".to_string(), Some(DateRange::from_year(2023))),
+        ]);
+        assert!(output.contains("2022 more < < < < = > > > > 2023 more\n"));
+        assert!(output.contains("Total contributions   365   365  +0 (+0.0%)\n"));
+        assert!(output.contains("More active days        1     1  364 equal\n"));
+    }
+
+    #[test]
+    fn diffs_two_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("diff.txt");
+        let args = Args {
+            slugs: vec!["alice".to_string(), "bob".to_string()],
+            diff: true,
+            ..args(Some(path.clone()))
+        };
+
+        run_with_source(&args, &StubSource::new()).unwrap();
+        let output = fs::read_to_string(path).unwrap();
+
+        assert!(output.contains("                     alice  bob  Change\n"));
+        assert!(output.contains("More active days         0    0  365 equal\n"));
+    }
 
-    let body = match response.status() {
-        StatusCode::OK => Ok(response.text().map_err(|_| GithubError::BadRequest)?),
-        StatusCode::NOT_FOUND => Err(GithubError::ProfileNotFound(profile_url.to_string())),
-        status => Err(GithubError::ScrapeFailure(status))
-    }?;
+    #[test]
+    fn error_if_diff_lacks_second_heatmap() {
+        let args = Args { diff: true, ..args(None) };
+        let error = run_with_source(&args, &StubSource::new()).unwrap_err();
 
-    Ok(Html::parse_document(&body))
+        assert_eq!(error.downcast_ref::<ArgumentError>(), Some(&ArgumentError::DiffOperands));
+        assert_eq!(ErrorReport::new(error.as_ref()).exit_code(), 2);
+    }
 }
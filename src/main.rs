@@ -1,12 +1,35 @@
 use clap::Parser;
-use std::process;
-use github_heatmap::{run, Args};
+use std::{env, process};
+use github_heatmap::{run, Args, ErrorFormatValues, ErrorReport};
 
 fn main() {
-    let args = Args::parse();  
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        Err(e) if e.use_stderr() && requests_json_errors(env::args()) => {
+            let report = ErrorReport::new(&e);
+            eprintln!("{}", report.to_json());
+            process::exit(report.exit_code());
+        },
+        Err(e) => e.exit(),
+    };
 
     if let Err(e) = run(&args) {
-        eprintln!("An error occurred: {e}");
-        process::exit(1);
+        let report = ErrorReport::new(e.as_ref());
+
+        match args.error_format {
+            ErrorFormatValues::Text => eprintln!("An error occurred: {e}"),
+            ErrorFormatValues::Json => eprintln!("{}", report.to_json()),
+        }
+
+        process::exit(report.exit_code());
     }
 }
+
+/// Determines whether JSON errors were requested by arguments which could not
+/// be parsed, so that parsing errors are reported in the requested format.
+fn requests_json_errors(args: impl Iterator<Item = String>) -> bool {
+    let args: Vec<String> = args.collect();
+
+    args.iter().any(|arg| arg == "--error-format=json")
+        || args.windows(2).any(|pair| pair[0] == "--error-format" && pair[1] == "json")
+}
@@ -0,0 +1,194 @@
+use std::{fs, path::PathBuf, time::Duration};
+use chrono::{DateTime, Utc};
+use crate::Heatmap;
+use super::DateRange;
+
+const CACHE_DIR_NAME: &str = "github-heatmap";
+
+/// An on-disk cache of parsed profile pages, keyed by profile slug and
+/// [`DateRange`].
+///
+/// Entries younger than the cache's time-to-live are used as is. Older entries
+/// are revalidated against Github with the `ETag` and `Last-Modified` validators
+/// of the cached response, avoiding the need to scrape the page again if it
+/// hasn't changed.
+///
+/// Entries are stored as JSON, and require the `serde` feature. Without it,
+/// nothing is stored and every lookup is a cache miss.
+///
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+    refresh: bool,
+}
+
+/// A cached [`Heatmap`], along with the validators of the response it was
+/// scraped from and the contribution years it lists.
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CacheEntry {
+    /// Time at which the response was last fetched or revalidated.
+    pub fetched_at: DateTime<Utc>,
+    /// `ETag` header of the cached response.
+    pub etag: Option<String>,
+    /// `Last-Modified` header of the cached response.
+    pub last_modified: Option<String>,
+    /// Heatmap scraped from the cached response.
+    pub heatmap: Heatmap,
+    /// Years linked beside the heatmap, if any. Entries stored without them are
+    /// read back with no years.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub years: Vec<i32>,
+}
+
+impl ResponseCache {
+    /// Constructs a new `ResponseCache` instance storing entries in the provided
+    /// directory, which will be created if it doesn't exist.
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        ResponseCache { dir: dir.into(), ttl, refresh: false }
+    }
+
+    /// Returns the default cache directory, located under the user's cache
+    /// directory (e.g. `$XDG_CACHE_HOME/github-heatmap` on Linux).
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join(CACHE_DIR_NAME))
+    }
+
+    /// Forces cached entries to be ignored, such that every response is fetched
+    /// again before being stored.
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Loads the cached entry for the provided profile slug and [`DateRange`], unless
+    /// the cache is being refreshed. Missing or unreadable entries are treated as
+    /// cache misses.
+    pub fn load(&self, slug: &str, range: Option<&DateRange>) -> Option<CacheEntry> {
+        if self.refresh {
+            return None;
+        }
+
+        let contents = fs::read_to_string(self.entry_path(slug, range)).ok()?;
+        Self::decode(&contents)
+    }
+
+    /// Stores an entry for the provided profile slug and [`DateRange`]. Failing to
+    /// write to the cache is not considered an error, as the entry will simply be
+    /// fetched again next time.
+    pub fn store(&self, slug: &str, range: Option<&DateRange>, entry: &CacheEntry) {
+        if let Some(contents) = Self::encode(entry) {
+            if fs::create_dir_all(&self.dir).is_ok() {
+                let _ = fs::write(self.entry_path(slug, range), contents);
+            }
+        }
+    }
+
+    /// Determines whether an entry is young enough to be used without being
+    /// revalidated.
+    pub fn is_fresh(&self, entry: &CacheEntry) -> bool {
+        (Utc::now() - entry.fetched_at)
+            .to_std()
+            .map_or(true, |age| age < self.ttl)
+    }
+
+    fn entry_path(&self, slug: &str, range: Option<&DateRange>) -> PathBuf {
+        let key = match range {
+            Some(range) => format!("{slug}-{}-{}", range.from, range.to),
+            None => format!("{slug}-latest")
+        };
+
+        self.dir.join(format!("{}.json", key.to_lowercase()))
+    }
+
+    #[cfg(feature = "serde")]
+    fn encode(entry: &CacheEntry) -> Option<String> {
+        serde_json::to_string(entry).ok()
+    }
+
+    #[cfg(not(feature = "serde"))]
+    fn encode(_entry: &CacheEntry) -> Option<String> {
+        None
+    }
+
+    #[cfg(feature = "serde")]
+    fn decode(contents: &str) -> Option<CacheEntry> {
+        serde_json::from_str(contents).ok()
+    }
+
+    #[cfg(not(feature = "serde"))]
+    fn decode(_contents: &str) -> Option<CacheEntry> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::{Contribution, ContributionWeek};
+
+    fn entry(fetched_at: DateTime<Utc>) -> CacheEntry {
+        let date = NaiveDate::from_ymd_opt(2022, 9, 18).unwrap();
+
+        CacheEntry {
+            fetched_at,
+            etag: Some("W/\"abc\"".to_string()),
+            last_modified: None,
+            heatmap: Heatmap {
+                contribution_weeks: vec![ContributionWeek {
+                    contributions: vec![
+                        Some(Contribution { date, heat_level: 2, count: 5 }),
+                        None, None, None, None, None, None,
+                    ]
+                }]
+            },
+            years: vec![2022],
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn stores_and_loads_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path(), Duration::from_secs(60));
+        let range = DateRange::from_year(2022);
+        let stored = entry(DateTime::from_timestamp(1_663_495_200, 0).unwrap());
+
+        cache.store("Torvalds", Some(&range), &stored);
+
+        assert!(dir.path().join("torvalds-2022-01-01-2022-12-31.json").exists());
+        assert_eq!(cache.load("Torvalds", Some(&range)), Some(stored));
+        assert_eq!(cache.load("Torvalds", None), None);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn loads_entries_stored_without_years() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path(), Duration::from_secs(60));
+        let mut stored = serde_json::to_value(entry(Utc::now())).unwrap();
+        stored.as_object_mut().unwrap().remove("years");
+        fs::write(dir.path().join("torvalds-latest.json"), stored.to_string()).unwrap();
+
+        assert_eq!(cache.load("torvalds", None).map(|entry| entry.years), Some(vec![]));
+    }
+
+    #[test]
+    fn ignores_entries_when_refreshing() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path(), Duration::from_secs(60));
+        cache.store("torvalds", None, &entry(Utc::now()));
+
+        assert_eq!(cache.with_refresh(true).load("torvalds", None), None);
+    }
+
+    #[test]
+    fn determines_entry_freshness() {
+        let cache = ResponseCache::new("unused", Duration::from_secs(60));
+
+        assert!(cache.is_fresh(&entry(Utc::now())));
+        assert!(!cache.is_fresh(&entry(Utc::now() - chrono::Duration::seconds(61))));
+    }
+}
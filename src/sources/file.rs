@@ -0,0 +1,138 @@
+use std::{error, fs, io::{self, Read}, path::{Path, PathBuf}, sync::Mutex};
+use scraper::Html;
+use crate::{Heatmap, InputError};
+use super::{ContributionSource, DateRange};
+
+const STDIN_PATH: &str = "-";
+
+/// A [`ContributionSource`] which reads a previously saved Github profile page,
+/// or just the contribution calendar fragment of one, from disk or standard input.
+///
+/// As the saved markup already determines which contributions are included,
+/// the requested profile slug and [`DateRange`] are ignored. The markup is read
+/// once and reused by later fetches, as standard input can only be read once.
+///
+#[derive(Debug)]
+pub struct FileSource {
+    path: PathBuf,
+    markup: Mutex<Option<String>>,
+}
+
+impl FileSource {
+    /// Constructs a new `FileSource` instance reading from the provided path.
+    /// A path of `-` reads from standard input instead.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileSource { path: path.into(), markup: Mutex::new(None) }
+    }
+
+    fn read_markup(&self) -> Result<String, InputError> {
+        let mut markup = self.markup.lock().expect("markup lock poisoned");
+
+        match &*markup {
+            Some(markup) => Ok(markup.clone()),
+            None => Ok(markup.insert(read_input(&self.path)?).clone()),
+        }
+    }
+}
+
+/// Reads the contents of the provided path, or of standard input if the path
+/// is `-`.
+pub(crate) fn read_input(path: &Path) -> Result<String, InputError> {
+    let read_error = |source| InputError::Read {
+        path: path.display().to_string(),
+        source
+    };
+
+    if path.as_os_str() == STDIN_PATH {
+        let mut contents = String::new();
+        io::stdin().read_to_string(&mut contents).map_err(read_error)?;
+        return Ok(contents);
+    }
+
+    fs::read_to_string(path).map_err(read_error)
+}
+
+impl ContributionSource for FileSource {
+    fn fetch_heatmap(
+        &self,
+        _slug: &str,
+        _range: Option<&DateRange>
+    ) -> Result<Heatmap, Box<dyn error::Error + Send + Sync>> {
+        let markup = self.read_markup()?;
+        let heatmap = Heatmap::from_document(&Html::parse_document(&markup))?;
+
+        Ok(heatmap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use crate::HeatmapError;
+
+    #[test]
+    fn reads_heatmap_from_calendar_fragment() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, r#"
+            <svg class="js-calendar-graph-svg">
+                <g>
+                    <g>
+                        <rect y='0' data-date='2022-09-18' data-level='1' class="ContributionCalendar-day">1 contribution on Sunday, September 18, 2022</rect>
+                    </g>
+                </g>
+            </svg>
+        "#).unwrap();
+
+        let heatmap = FileSource::new(file.path()).fetch_heatmap("torvalds", None).unwrap();
+        let date = chrono::NaiveDate::from_ymd_opt(2022, 9, 18).unwrap();
+
+        assert_eq!(heatmap.get_contribution(date).map(|contribution| contribution.count), Some(1))
+    }
+
+    #[test]
+    fn reads_markup_once_when_fetching_several_years() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, r#"
+            <svg class="js-calendar-graph-svg">
+                <g>
+                    <g>
+                        <rect y='60' data-date='2021-12-30' data-level='1' class="ContributionCalendar-day">1 contribution on Thursday, December 30, 2021</rect>
+                        <rect y='75' data-date='2021-12-31' data-level='2' class="ContributionCalendar-day">2 contributions on Friday, December 31, 2021</rect>
+                        <rect y='90' data-date='2022-01-01' data-level='3' class="ContributionCalendar-day">3 contributions on Saturday, January 1, 2022</rect>
+                    </g>
+                </g>
+            </svg>
+        "#).unwrap();
+
+        let source = FileSource::new(file.path());
+        let range = DateRange {
+            from: chrono::NaiveDate::from_ymd_opt(2021, 12, 31).unwrap(),
+            to: chrono::NaiveDate::from_ymd_opt(2022, 1, 1).unwrap(),
+        };
+        let heatmap = source.fetch_range("torvalds", &range).unwrap();
+        let counts: Vec<_> = heatmap.days().map(|day| day.count).collect();
+
+        assert_eq!(counts, vec![2, 3]);
+
+        file.close().unwrap();
+        assert!(source.fetch_heatmap("torvalds", None).is_ok());
+    }
+
+    #[test]
+    fn error_if_no_heatmap_in_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "<html><body><p>Not a profile page</p></body></html>").unwrap();
+
+        let error = FileSource::new(file.path()).fetch_heatmap("torvalds", None).unwrap_err();
+
+        assert!(matches!(error.downcast_ref(), Some(HeatmapError::QueryElement { .. })))
+    }
+
+    #[test]
+    fn error_if_file_missing() {
+        let error = FileSource::new("does/not/exist.html").read_markup().unwrap_err();
+
+        assert!(matches!(error, InputError::Read { path, .. } if path == "does/not/exist.html"))
+    }
+}
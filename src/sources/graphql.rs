@@ -0,0 +1,371 @@
+use std::error;
+use chrono::NaiveDate;
+use reqwest::{blocking::Client, header::USER_AGENT, StatusCode};
+use serde_json::{json, Value};
+use crate::{Contribution, ContributionWeek, GithubError, Heatmap};
+use crate::heatmap::DAYS_IN_WEEK;
+use super::{ContributionSource, DateRange, RetryPolicy};
+
+const GRAPHQL_URL: &str = "https://api.github.com/graphql";
+
+const CONTRIBUTIONS_QUERY: &str = "
+query($login: String!, $from: DateTime, $to: DateTime) {
+  user(login: $login) {
+    contributionsCollection(from: $from, to: $to) {
+      contributionCalendar {
+        weeks {
+          contributionDays {
+            date
+            contributionCount
+            contributionLevel
+            weekday
+          }
+        }
+      }
+    }
+  }
+}";
+
+const YEARS_QUERY: &str = "
+query($login: String!) {
+  user(login: $login) {
+    contributionsCollection {
+      contributionYears
+    }
+  }
+}";
+
+/// A [`ContributionSource`] which queries the `contributionsCollection.contributionCalendar`
+/// field of the Github GraphQL API, authenticated with a personal access token.
+///
+#[derive(Debug)]
+pub struct GraphQlSource {
+    token: String,
+    endpoint: String,
+    retry_policy: RetryPolicy,
+}
+
+impl GraphQlSource {
+    /// Constructs a new `GraphQlSource` instance, which will authenticate against
+    /// the Github GraphQL API with the provided personal access token.
+    pub fn new(token: &str) -> Self {
+        GraphQlSource {
+            token: token.to_string(),
+            endpoint: GRAPHQL_URL.to_string(),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Overrides the URL of the GraphQL endpoint being queried, e.g. to target
+    /// a Github Enterprise instance or a mock server.
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_string();
+        self
+    }
+
+    /// Overrides the [`RetryPolicy`] used when querying the GraphQL API.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    fn query_variables(slug: &str, range: Option<&DateRange>) -> Value {
+        match range {
+            Some(range) => json!({
+                "login": slug,
+                "from": format!("{}T00:00:00Z", range.from),
+                "to": format!("{}T23:59:59Z", range.to),
+            }),
+            None => json!({ "login": slug }),
+        }
+    }
+
+    fn query_calendar(&self, slug: &str, range: Option<&DateRange>) -> Result<Value, GithubError> {
+        self.query(CONTRIBUTIONS_QUERY, Self::query_variables(slug, range))
+    }
+
+    fn query(&self, query: &str, variables: Value) -> Result<Value, GithubError> {
+        let body = json!({ "query": query, "variables": variables });
+
+        let client = Client::new();
+        let response = self.retry_policy.send(|| {
+            client
+                .post(&self.endpoint)
+                .bearer_auth(&self.token)
+                .header(USER_AGENT, env!("CARGO_PKG_NAME"))
+                .json(&body)
+                .send()
+        })?;
+
+        match response.status() {
+            StatusCode::OK => Ok(response.json()?),
+            StatusCode::UNAUTHORIZED => Err(GithubError::Unauthorized),
+            status => Err(GithubError::ScrapeFailure(status))
+        }
+    }
+
+    fn heatmap_from_response(slug: &str, response: &Value) -> Result<Heatmap, GithubError> {
+        Self::check_errors(slug, response)?;
+
+        let contribution_weeks = response
+            .pointer("/data/user/contributionsCollection/contributionCalendar/weeks")
+            .and_then(Value::as_array)
+            .ok_or_else(|| GithubError::UnexpectedResponse("missing contribution calendar".to_string()))?
+            .iter()
+            .map(Self::contribution_week)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Heatmap { contribution_weeks })
+    }
+
+    fn years_from_response(slug: &str, response: &Value) -> Result<Vec<i32>, GithubError> {
+        Self::check_errors(slug, response)?;
+
+        let mut years = response
+            .pointer("/data/user/contributionsCollection/contributionYears")
+            .and_then(Value::as_array)
+            .ok_or_else(|| GithubError::UnexpectedResponse("missing contribution years".to_string()))?
+            .iter()
+            .map(|year| year
+                .as_i64()
+                .map(|year| year as i32)
+                .ok_or_else(|| GithubError::UnexpectedResponse("invalid contribution year".to_string())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        years.sort_unstable();
+
+        Ok(years)
+    }
+
+    fn check_errors(slug: &str, response: &Value) -> Result<(), GithubError> {
+        match response["errors"].as_array().and_then(|errors| errors.first()) {
+            Some(error) => Err(Self::graphql_error(slug, error)),
+            None => Ok(()),
+        }
+    }
+
+    fn graphql_error(slug: &str, error: &Value) -> GithubError {
+        let kind = error["type"].as_str().unwrap_or_default().to_string();
+        let message = error["message"].as_str().unwrap_or_default().to_string();
+
+        match kind.as_str() {
+            "NOT_FOUND" => GithubError::ProfileNotFound(format!("https://github.com/{slug}")),
+            "RATE_LIMITED" => GithubError::RateLimited(message),
+            _ => GithubError::GraphQlQuery { kind, message }
+        }
+    }
+
+    fn contribution_week(week: &Value) -> Result<ContributionWeek, GithubError> {
+        let mut contributions: Vec<Option<Contribution>> = vec![None; DAYS_IN_WEEK];
+
+        let days = week["contributionDays"]
+            .as_array()
+            .ok_or_else(|| GithubError::UnexpectedResponse("missing contribution days".to_string()))?;
+
+        for day in days {
+            let (weekday, contribution) = Self::contribution_day(day)?;
+            contributions[weekday] = Some(contribution);
+        }
+
+        Ok(ContributionWeek { contributions })
+    }
+
+    fn contribution_day(day: &Value) -> Result<(usize, Contribution), GithubError> {
+        let unexpected = |field: &str| GithubError::UnexpectedResponse(format!("invalid contribution day field '{field}'"));
+
+        let date = day["date"]
+            .as_str()
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            .ok_or_else(|| unexpected("date"))?;
+
+        let count = day["contributionCount"]
+            .as_u64()
+            .ok_or_else(|| unexpected("contributionCount"))? as usize;
+
+        let heat_level = match day["contributionLevel"].as_str() {
+            Some("NONE") => 0,
+            Some("FIRST_QUARTILE") => 1,
+            Some("SECOND_QUARTILE") => 2,
+            Some("THIRD_QUARTILE") => 3,
+            Some("FOURTH_QUARTILE") => 4,
+            _ => return Err(unexpected("contributionLevel"))
+        };
+
+        let weekday = day["weekday"]
+            .as_u64()
+            .map(|weekday| weekday as usize)
+            .filter(|weekday| *weekday < DAYS_IN_WEEK)
+            .ok_or_else(|| unexpected("weekday"))?;
+
+        Ok((weekday, Contribution { date, heat_level, count }))
+    }
+}
+
+impl ContributionSource for GraphQlSource {
+    fn fetch_heatmap(
+        &self,
+        slug: &str,
+        range: Option<&DateRange>
+    ) -> Result<Heatmap, Box<dyn error::Error + Send + Sync>> {
+        let response = self.query_calendar(slug, range)?;
+        let heatmap = Self::heatmap_from_response(slug, &response)?;
+
+        Ok(heatmap)
+    }
+
+    fn contribution_years(&self, slug: &str) -> Result<Vec<i32>, Box<dyn error::Error + Send + Sync>> {
+        let response = self.query(YEARS_QUERY, json!({ "login": slug }))?;
+        let years = Self::years_from_response(slug, &response)?;
+
+        Ok(years)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::mock::{serve, MockResponse};
+
+    const CALENDAR_RESPONSE: &str = r#"{
+        "data": {
+            "user": {
+                "contributionsCollection": {
+                    "contributionCalendar": {
+                        "weeks": [
+                            {
+                                "contributionDays": [
+                                    { "date": "2022-09-16", "contributionCount": 0, "contributionLevel": "NONE", "weekday": 5 },
+                                    { "date": "2022-09-17", "contributionCount": 12, "contributionLevel": "FOURTH_QUARTILE", "weekday": 6 }
+                                ]
+                            },
+                            {
+                                "contributionDays": [
+                                    { "date": "2022-09-18", "contributionCount": 1, "contributionLevel": "FIRST_QUARTILE", "weekday": 0 }
+                                ]
+                            }
+                        ]
+                    }
+                }
+            }
+        }
+    }"#;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn fetches_heatmap_from_graphql_api() {
+        let (url, handle) = serve(vec![MockResponse::new(200, CALENDAR_RESPONSE)]);
+        let source = GraphQlSource::new("secret").with_endpoint(&format!("{url}/graphql"));
+        let heatmap = source.fetch_heatmap("torvalds", None).unwrap();
+
+        let expected = Heatmap {
+            contribution_weeks: vec![
+                ContributionWeek {
+                    contributions: vec![
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        Some(Contribution { date: date("2022-09-16"), heat_level: 0, count: 0 }),
+                        Some(Contribution { date: date("2022-09-17"), heat_level: 4, count: 12 }),
+                    ]
+                },
+                ContributionWeek {
+                    contributions: vec![
+                        Some(Contribution { date: date("2022-09-18"), heat_level: 1, count: 1 }),
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                    ]
+                }
+            ]
+        };
+
+        assert_eq!(heatmap, expected);
+        assert_eq!(handle.join().unwrap()[0].header("Authorization"), Some("Bearer secret"));
+    }
+
+    #[test]
+    fn fetches_contribution_years() {
+        let response = r#"{ "data": { "user": { "contributionsCollection": { "contributionYears": [2022, 2020, 2021] } } } }"#;
+        let (url, _) = serve(vec![MockResponse::new(200, response)]);
+        let source = GraphQlSource::new("secret").with_endpoint(&url);
+
+        assert_eq!(source.contribution_years("torvalds").unwrap(), vec![2020, 2021, 2022]);
+    }
+
+    #[test]
+    fn error_if_token_rejected() {
+        let (url, _) = serve(vec![MockResponse::new(401, r#"{ "message": "Bad credentials" }"#)]);
+        let source = GraphQlSource::new("expired").with_endpoint(&url);
+        let result = source.query_calendar("torvalds", None);
+
+        assert!(matches!(result, Err(GithubError::Unauthorized)))
+    }
+
+    #[test]
+    fn error_if_response_not_json() {
+        let (url, _) = serve(vec![MockResponse::new(200, "<html>Unicorn!</html>")]);
+        let source = GraphQlSource::new("secret").with_endpoint(&url);
+        let result = source.query_calendar("torvalds", None);
+
+        assert!(matches!(result, Err(GithubError::Decode(_))))
+    }
+
+    #[test]
+    fn error_if_user_not_found() {
+        let response = json!({
+            "data": { "user": null },
+            "errors": [{ "type": "NOT_FOUND", "message": "Could not resolve to a User with the login of 'nobody'." }]
+        });
+
+        assert!(matches!(
+            GraphQlSource::heatmap_from_response("nobody", &response),
+            Err(GithubError::ProfileNotFound(url)) if url == "https://github.com/nobody"
+        ))
+    }
+
+    #[test]
+    fn error_if_rate_limited() {
+        let response = json!({
+            "errors": [{ "type": "RATE_LIMITED", "message": "API rate limit exceeded" }]
+        });
+
+        assert!(matches!(
+            GraphQlSource::heatmap_from_response("torvalds", &response),
+            Err(GithubError::RateLimited(message)) if message == "API rate limit exceeded"
+        ))
+    }
+
+    #[test]
+    fn error_if_unknown_contribution_level() {
+        let response = json!({
+            "data": { "user": { "contributionsCollection": { "contributionCalendar": { "weeks": [
+                { "contributionDays": [{ "date": "2022-09-18", "contributionCount": 1, "contributionLevel": "LOTS", "weekday": 0 }] }
+            ]}}}}
+        });
+
+        assert!(matches!(
+            GraphQlSource::heatmap_from_response("torvalds", &response),
+            Err(GithubError::UnexpectedResponse(message)) if message == "invalid contribution day field 'contributionLevel'"
+        ))
+    }
+
+    #[test]
+    fn builds_query_variables_for_date_range() {
+        let range = DateRange::from_year(2022);
+        let variables = GraphQlSource::query_variables("torvalds", Some(&range));
+
+        assert_eq!(variables, json!({
+            "login": "torvalds",
+            "from": "2022-01-01T00:00:00Z",
+            "to": "2022-12-31T23:59:59Z",
+        }))
+    }
+}
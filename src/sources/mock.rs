@@ -0,0 +1,69 @@
+use std::thread::{self, JoinHandle};
+use tiny_http::{Header, Response, Server};
+
+/// A canned response served by [`serve`].
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: String,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: &str) -> Self {
+        MockResponse { status, headers: vec![], body: body.to_string() }
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: &str) -> Self {
+        self.headers.push((name, value.to_string()));
+        self
+    }
+}
+
+/// A request received by [`serve`].
+#[derive(Debug)]
+pub struct RecordedRequest {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Serves the provided responses in order, one per request, returning the base
+/// URL of the server and a handle resolving to the received requests.
+pub fn serve(responses: Vec<MockResponse>) -> (String, JoinHandle<Vec<RecordedRequest>>) {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+
+    let handle = thread::spawn(move || {
+        let mut requests = vec![];
+
+        for mock in responses {
+            let request = server.recv().unwrap();
+            requests.push(RecordedRequest {
+                url: request.url().to_string(),
+                headers: request
+                    .headers()
+                    .iter()
+                    .map(|header| (header.field.to_string(), header.value.to_string()))
+                    .collect(),
+            });
+
+            let mut response = Response::from_string(mock.body).with_status_code(mock.status);
+            for (name, value) in mock.headers {
+                response.add_header(Header::from_bytes(name, value).unwrap());
+            }
+            request.respond(response).unwrap();
+        }
+
+        requests
+    });
+
+    (url, handle)
+}
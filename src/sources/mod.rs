@@ -0,0 +1,243 @@
+mod cache;
+mod file;
+mod graphql;
+mod profile;
+mod retry;
+
+#[cfg(test)]
+mod mock;
+
+pub use cache::{CacheEntry, ResponseCache};
+pub use file::FileSource;
+pub(crate) use file::read_input;
+pub use graphql::GraphQlSource;
+pub use profile::ProfileScraper;
+pub use retry::RetryPolicy;
+use std::{collections::BTreeMap, error};
+use chrono::{Datelike, NaiveDate};
+use crate::Heatmap;
+
+/// An inclusive range of calendar dates to fetch contributions for.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DateRange {
+    /// First calendar date included in the range.
+    pub from: NaiveDate,
+    /// Last calendar date included in the range.
+    pub to: NaiveDate,
+}
+
+impl DateRange {
+    /// Constructs a new `DateRange` instance spanning an entire calendar year,
+    /// from the 1st of January until the 31st of December.
+    ///
+    /// # Panics
+    /// A panic will occur if the provided year falls outside of the range of
+    /// dates supported by [`NaiveDate`].
+    ///
+    pub fn from_year(year: i32) -> Self {
+        DateRange {
+            from: NaiveDate::from_ymd_opt(year, 1, 1).unwrap(),
+            to: NaiveDate::from_ymd_opt(year, 12, 31).unwrap(),
+        }
+    }
+
+    /// Constructs a new `DateRange` instance spanning from the 1st of January
+    /// of the earliest provided year until the 31st of December of the latest.
+    ///
+    /// Returns `None` if no years are provided.
+    ///
+    pub fn spanning_years(years: &[i32]) -> Option<Self> {
+        let first = years.iter().min()?;
+        let last = years.iter().max()?;
+
+        Some(DateRange {
+            from: DateRange::from_year(*first).from,
+            to: DateRange::from_year(*last).to,
+        })
+    }
+
+    /// Returns the calendar year that the range begins in.
+    pub fn year(&self) -> i32 {
+        self.from.year()
+    }
+
+    /// Returns whether the provided date falls within the range.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        (self.from..=self.to).contains(&date)
+    }
+
+    /// Returns the entire calendar years overlapping the range, in order.
+    pub fn years(&self) -> Vec<DateRange> {
+        (self.from.year()..=self.to.year()).map(DateRange::from_year).collect()
+    }
+}
+
+/// A `ContributionSource` represents a backend capable of producing a
+/// [`Heatmap`] of contributions for a given user.
+///
+/// The scraped Github profile page ([`ProfileScraper`]) is the default
+/// implementation, however any other backend (local files, APIs, other forges)
+/// may implement this trait to be used by [`crate::run`].
+///
+pub trait ContributionSource {
+    /// Fetches the contributions belonging to the provided profile slug, and
+    /// constructs a [`Heatmap`] from them.
+    ///
+    /// If no [`DateRange`] is provided, contributions will be fetched for the
+    /// source's default period, typically the last 365 days.
+    ///
+    /// # Errors
+    /// Returns an error if contributions could not be fetched, or if the fetched
+    /// contributions could not be parsed into a [`Heatmap`].
+    ///
+    fn fetch_heatmap(
+        &self,
+        slug: &str,
+        range: Option<&DateRange>
+    ) -> Result<Heatmap, Box<dyn error::Error + Send + Sync>>;
+
+    /// Fetches the contributions belonging to the provided profile slug over an
+    /// arbitrary [`DateRange`], which may cross calendar years.
+    ///
+    /// A [`Heatmap`] is fetched for every calendar year overlapping the range,
+    /// and their contributions are stitched into a single continuous `Heatmap`
+    /// trimmed to exactly the provided range.
+    ///
+    /// # Errors
+    /// Returns the first error encountered while fetching any of the years.
+    ///
+    fn fetch_range(
+        &self,
+        slug: &str,
+        range: &DateRange
+    ) -> Result<Heatmap, Box<dyn error::Error + Send + Sync>> {
+        let mut contributions = BTreeMap::new();
+
+        for year in range.years() {
+            let heatmap = self.fetch_heatmap(slug, Some(&year))?;
+
+            for contribution in heatmap.days().filter(|contribution| range.contains(contribution.date)) {
+                contributions.insert(contribution.date, contribution.clone());
+            }
+        }
+
+        Ok(Heatmap::from_contributions(contributions.into_values().collect()))
+    }
+
+    /// Determines the calendar years that the provided profile slug has
+    /// contributions in, in ascending order.
+    ///
+    /// Unless overridden, the years spanned by the source's default period are
+    /// returned, which suits sources without any notion of a profile history.
+    ///
+    /// # Errors
+    /// Returns an error if the years could not be determined.
+    ///
+    fn contribution_years(&self, slug: &str) -> Result<Vec<i32>, Box<dyn error::Error + Send + Sync>> {
+        let heatmap = self.fetch_heatmap(slug, None)?;
+        let mut years: Vec<i32> = heatmap.days().map(|contribution| contribution.date.year()).collect();
+
+        years.sort_unstable();
+        years.dedup();
+
+        Ok(years)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Contribution;
+
+    #[test]
+    fn constructs_date_range_from_year() {
+        let range = DateRange::from_year(2022);
+
+        assert_eq!(range.from, NaiveDate::from_ymd_opt(2022, 1, 1).unwrap());
+        assert_eq!(range.to, NaiveDate::from_ymd_opt(2022, 12, 31).unwrap());
+        assert_eq!(range.year(), 2022);
+    }
+
+    #[test]
+    fn constructs_date_range_spanning_years() {
+        let range = DateRange::spanning_years(&[2021, 2019, 2020]).unwrap();
+
+        assert_eq!(range.from, NaiveDate::from_ymd_opt(2019, 1, 1).unwrap());
+        assert_eq!(range.to, NaiveDate::from_ymd_opt(2021, 12, 31).unwrap());
+        assert_eq!(DateRange::spanning_years(&[]), None);
+    }
+
+    #[test]
+    fn splits_date_range_into_years() {
+        let range = DateRange {
+            from: NaiveDate::from_ymd_opt(2023, 4, 1).unwrap(),
+            to: NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+        };
+
+        assert_eq!(range.years(), vec![DateRange::from_year(2023), DateRange::from_year(2024)]);
+        assert!(range.contains(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()));
+        assert!(!range.contains(NaiveDate::from_ymd_opt(2023, 3, 31).unwrap()));
+    }
+
+    #[test]
+    fn stitches_years_into_date_range() {
+        struct YearSource;
+
+        impl ContributionSource for YearSource {
+            fn fetch_heatmap(
+                &self,
+                _slug: &str,
+                range: Option<&DateRange>
+            ) -> Result<Heatmap, Box<dyn error::Error + Send + Sync>> {
+                let range = range.unwrap();
+                let contributions = range.from
+                    .iter_days()
+                    .take_while(|date| *date <= range.to)
+                    .map(|date| Contribution { date, heat_level: 1, count: date.year() as usize })
+                    .collect();
+
+                Ok(Heatmap::from_contributions(contributions))
+            }
+        }
+
+        let range = DateRange {
+            from: NaiveDate::from_ymd_opt(2023, 12, 30).unwrap(),
+            to: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+        };
+        let heatmap = YearSource.fetch_range("torvalds", &range).unwrap();
+        let days: Vec<_> = heatmap.days()
+            .map(|day| (day.date.to_string(), day.count))
+            .collect();
+
+        assert_eq!(days, vec![
+            ("2023-12-30".to_string(), 2023),
+            ("2023-12-31".to_string(), 2023),
+            ("2024-01-01".to_string(), 2024),
+            ("2024-01-02".to_string(), 2024),
+        ]);
+        assert_eq!(heatmap.contribution_weeks.len(), 2);
+    }
+
+    #[test]
+    fn determines_years_of_default_period() {
+        struct LatestSource;
+
+        impl ContributionSource for LatestSource {
+            fn fetch_heatmap(
+                &self,
+                _slug: &str,
+                _range: Option<&DateRange>
+            ) -> Result<Heatmap, Box<dyn error::Error + Send + Sync>> {
+                let contribution = |date| Contribution { date, heat_level: 0, count: 0 };
+
+                Ok(Heatmap::from_contributions(vec![
+                    contribution(NaiveDate::from_ymd_opt(2022, 12, 31).unwrap()),
+                    contribution(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()),
+                    contribution(NaiveDate::from_ymd_opt(2023, 1, 2).unwrap()),
+                ]))
+            }
+        }
+
+        assert_eq!(LatestSource.contribution_years("torvalds").unwrap(), vec![2022, 2023]);
+    }
+}
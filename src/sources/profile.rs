@@ -0,0 +1,315 @@
+use std::error;
+use chrono::Utc;
+use reqwest::{
+    blocking::{Client, Response},
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, USER_AGENT},
+    StatusCode
+};
+use scraper::{Html, Selector};
+use crate::{GithubError, Heatmap, HeatmapError};
+use super::{CacheEntry, ContributionSource, DateRange, ResponseCache, RetryPolicy};
+
+const PROFILE_URL: &str = "https://github.com";
+const YEAR_LINK_SELECTOR: &str = "a.js-year-link";
+
+/// A [`ContributionSource`] which scrapes the contribution heatmap SVG element
+/// from a Github profile page (e.g. <https://github.com/torvalds>).
+///
+#[derive(Debug)]
+pub struct ProfileScraper {
+    base_url: String,
+    retry_policy: RetryPolicy,
+    cache: Option<ResponseCache>,
+}
+
+impl Default for ProfileScraper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProfileScraper {
+    /// Constructs a new `ProfileScraper` instance.
+    pub fn new() -> Self {
+        ProfileScraper {
+            base_url: PROFILE_URL.to_string(),
+            retry_policy: RetryPolicy::default(),
+            cache: None,
+        }
+    }
+
+    /// Overrides the base URL that profile pages are fetched from, e.g. to target
+    /// a Github Enterprise instance or a mock server.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Overrides the [`RetryPolicy`] used when fetching profile pages.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Enables caching of scraped profile pages in the provided [`ResponseCache`].
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    fn profile_url(&self, slug: &str, range: Option<&DateRange>) -> String {
+        let base_url = &self.base_url;
+
+        match range {
+            Some(range) => format!("{base_url}/{slug}?from={}&to={}", range.from, range.to),
+            None => format!("{base_url}/{slug}")
+        }
+    }
+
+    /// Requests a profile page, revalidating the provided cache entry if any.
+    /// Returns `None` if Github reports that the cached page has not been modified.
+    fn get_github_profile(
+        &self,
+        profile_url: &str,
+        cached: Option<&CacheEntry>
+    ) -> Result<Option<Response>, GithubError> {
+        let client = Client::new();
+        let response = self.retry_policy.send(|| {
+            let mut request = client.get(profile_url).header(USER_AGENT, env!("CARGO_PKG_NAME"));
+
+            if let Some(etag) = cached.and_then(|entry| entry.etag.as_deref()) {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = cached.and_then(|entry| entry.last_modified.as_deref()) {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+
+            request.send()
+        })?;
+
+        match response.status() {
+            StatusCode::OK => Ok(Some(response)),
+            StatusCode::NOT_MODIFIED if cached.is_some() => Ok(None),
+            StatusCode::NOT_FOUND => Err(GithubError::ProfileNotFound(profile_url.to_string())),
+            status => Err(GithubError::ScrapeFailure(status))
+        }
+    }
+
+    /// Loads the cached entry of a profile page, or fetches it if missing, stale or
+    /// not `reusable`, storing the result in the cache.
+    fn fetch_entry(
+        &self,
+        slug: &str,
+        range: Option<&DateRange>,
+        reusable: fn(&CacheEntry) -> bool
+    ) -> Result<CacheEntry, Box<dyn error::Error + Send + Sync>> {
+        let loaded = self.cache.as_ref().and_then(|cache| cache.load(slug, range)).filter(reusable);
+        let cached = match (&self.cache, loaded) {
+            (Some(cache), Some(entry)) if cache.is_fresh(&entry) => return Ok(entry),
+            (_, cached) => cached,
+        };
+
+        let entry = match (self.get_github_profile(&self.profile_url(slug, range), cached.as_ref())?, cached) {
+            (Some(response), _) => Self::scrape_page(response)?,
+            (None, Some(entry)) => CacheEntry { fetched_at: Utc::now(), ..entry },
+            (None, None) => unreachable!("profile pages are only revalidated if cached"),
+        };
+
+        if let Some(cache) = &self.cache {
+            cache.store(slug, range, &entry);
+        }
+
+        Ok(entry)
+    }
+
+    fn scrape_page(response: Response) -> Result<CacheEntry, Box<dyn error::Error + Send + Sync>> {
+        let header = |name| response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
+
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let body = response.text().map_err(GithubError::from)?;
+        let document = Html::parse_document(&body);
+        let heatmap = Heatmap::from_document(&document)?;
+        let years = Self::scrape_years(&document).unwrap_or_default();
+
+        Ok(CacheEntry { fetched_at: Utc::now(), etag, last_modified, heatmap, years })
+    }
+
+    /// Scrapes the years listed beside the contribution heatmap, which link to
+    /// the heatmap of each year the profile has been active in.
+    fn scrape_years(document: &Html) -> Result<Vec<i32>, HeatmapError> {
+        let selector = Selector::parse(YEAR_LINK_SELECTOR).unwrap();
+        let mut years = document
+            .select(&selector)
+            .map(|el| el.text().collect::<String>().trim().parse().map_err(|_| HeatmapError::ParseAttribute {
+                attr: "text".to_string(),
+                on_alias: "year link".to_string(),
+            }))
+            .collect::<Result<Vec<i32>, _>>()?;
+
+        years.sort_unstable();
+        years.dedup();
+
+        Ok(years)
+    }
+}
+
+impl ContributionSource for ProfileScraper {
+    fn fetch_heatmap(
+        &self,
+        slug: &str,
+        range: Option<&DateRange>
+    ) -> Result<Heatmap, Box<dyn error::Error + Send + Sync>> {
+        Ok(self.fetch_entry(slug, range, |_| true)?.heatmap)
+    }
+
+    /// Reads the years linked beside the heatmap of the profile page, which is
+    /// cached alongside the heatmap of the default period. Cached pages listing
+    /// no years are fetched again, as they may have been stored without them.
+    fn contribution_years(&self, slug: &str) -> Result<Vec<i32>, Box<dyn error::Error + Send + Sync>> {
+        let entry = self.fetch_entry(slug, None, |entry| !entry.years.is_empty())?;
+
+        if entry.years.is_empty() {
+            return Err(HeatmapError::QueryElement {
+                alias: "year links".to_string(),
+                selector: YEAR_LINK_SELECTOR.to_string(),
+            }.into());
+        }
+
+        Ok(entry.years)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::sources::mock::{serve, MockResponse};
+
+    const PROFILE_PAGE: &str = r#"
+        <svg class="js-calendar-graph-svg">
+            <g>
+                <g>
+                    <rect y='0' data-date='2022-09-18' data-level='2' class="ContributionCalendar-day">5 contributions on Sunday, September 18, 2022</rect>
+                </g>
+            </g>
+        </svg>
+    "#;
+
+    const YEAR_LINKS: &str = r#"
+        <a class="js-year-link filter-item" id="year-link-2022" href="/torvalds?from=2022-12-01&to=2022-12-31">2022</a>
+        <a class="js-year-link filter-item" id="year-link-2021" href="/torvalds?from=2021-12-01&to=2021-12-31">2021</a>
+    "#;
+
+    #[test]
+    fn builds_default_profile_url() {
+        let url = ProfileScraper::new().profile_url("torvalds", None);
+        assert_eq!(url, "https://github.com/torvalds")
+    }
+
+    #[test]
+    fn builds_profile_url_for_date_range() {
+        let range = DateRange::from_year(2022);
+        let url = ProfileScraper::new().profile_url("torvalds", Some(&range));
+
+        assert_eq!(url, "https://github.com/torvalds?from=2022-01-01&to=2022-12-31")
+    }
+
+    #[test]
+    fn fetches_heatmap_after_transient_failure() {
+        let (url, handle) = serve(vec![
+            MockResponse::new(502, "Bad Gateway"),
+            MockResponse::new(200, PROFILE_PAGE),
+        ]);
+
+        let retry_policy = RetryPolicy { base_delay: Duration::ZERO, ..Default::default() };
+        let source = ProfileScraper::new().with_base_url(&url).with_retry_policy(retry_policy);
+        let heatmap = source.fetch_heatmap("torvalds", None).unwrap();
+        let requests = handle.join().unwrap();
+
+        assert_eq!(heatmap.contribution_weeks.len(), 1);
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].url, "/torvalds");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serves_fresh_heatmap_from_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, _) = serve(vec![MockResponse::new(200, PROFILE_PAGE)]);
+        let source = ProfileScraper::new()
+            .with_base_url(&url)
+            .with_cache(ResponseCache::new(dir.path(), Duration::from_secs(60)));
+
+        let fetched = source.fetch_heatmap("torvalds", None).unwrap();
+        // The mock server only serves a single response, so a second request would fail.
+        let cached = source.fetch_heatmap("torvalds", None).unwrap();
+
+        assert_eq!(fetched, cached);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn revalidates_stale_heatmap() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, handle) = serve(vec![
+            MockResponse::new(200, PROFILE_PAGE).with_header("ETag", "\"v1\""),
+            MockResponse::new(304, ""),
+        ]);
+        let source = ProfileScraper::new()
+            .with_base_url(&url)
+            .with_cache(ResponseCache::new(dir.path(), Duration::ZERO));
+
+        let fetched = source.fetch_heatmap("torvalds", None).unwrap();
+        let revalidated = source.fetch_heatmap("torvalds", None).unwrap();
+        let requests = handle.join().unwrap();
+
+        assert_eq!(fetched, revalidated);
+        assert_eq!(requests[0].header("If-None-Match"), None);
+        assert_eq!(requests[1].header("If-None-Match"), Some("\"v1\""));
+    }
+
+    #[test]
+    fn scrapes_contribution_years() {
+        let (url, _) = serve(vec![MockResponse::new(200, &format!("{PROFILE_PAGE}{YEAR_LINKS}"))]);
+        let source = ProfileScraper::new().with_base_url(&url);
+
+        assert_eq!(source.contribution_years("torvalds").unwrap(), vec![2021, 2022]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serves_contribution_years_from_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, _) = serve(vec![MockResponse::new(200, &format!("{PROFILE_PAGE}{YEAR_LINKS}"))]);
+        let source = ProfileScraper::new()
+            .with_base_url(&url)
+            .with_cache(ResponseCache::new(dir.path(), Duration::from_secs(60)));
+
+        source.fetch_heatmap("torvalds", None).unwrap();
+        // The mock server only serves a single response, so the years must come from the cache.
+        assert_eq!(source.contribution_years("torvalds").unwrap(), vec![2021, 2022]);
+    }
+
+    #[test]
+    fn error_if_no_year_links() {
+        let (url, _) = serve(vec![MockResponse::new(200, PROFILE_PAGE)]);
+        let source = ProfileScraper::new().with_base_url(&url);
+        let error = source.contribution_years("torvalds").unwrap_err();
+
+        assert!(matches!(error.downcast_ref(), Some(HeatmapError::QueryElement { .. })));
+    }
+
+    #[test]
+    fn error_if_profile_not_found() {
+        let (url, _) = serve(vec![MockResponse::new(404, "Not Found")]);
+        let source = ProfileScraper::new().with_base_url(&url);
+        let error = source.get_github_profile(&source.profile_url("nobody", None), None).unwrap_err();
+
+        assert!(matches!(error, GithubError::ProfileNotFound(_)))
+    }
+}
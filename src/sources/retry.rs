@@ -0,0 +1,304 @@
+use std::{thread, time::Duration};
+use chrono::{DateTime, Utc};
+use reqwest::{blocking::Response, header::{HeaderMap, RETRY_AFTER}, StatusCode};
+use crate::GithubError;
+
+const RATE_LIMIT_REMAINING: &str = "x-ratelimit-remaining";
+const RATE_LIMIT_RESET: &str = "x-ratelimit-reset";
+
+/// Determines how requests to Github services are retried after transient
+/// failures, such as 429 and 5xx responses, 403 responses with an exhausted
+/// rate limit, timeouts and refused connections.
+///
+/// Delays grow exponentially with each attempt, with random jitter applied.
+/// Delays requested by Github through the `Retry-After` or `X-RateLimit-Reset`
+/// headers take precedence, however requests are not retried if Github asks
+/// to wait for longer than `max_delay`.
+///
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries following the initial attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every subsequent retry.
+    pub base_delay: Duration,
+    /// Upper bound for the delay between two attempts.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Sends a request, retrying it according to the policy. The provided closure
+    /// is invoked once per attempt.
+    ///
+    /// Responses with a status code other than 429 or 5xx are returned as is, to
+    /// be handled by the caller, unless they are 403 responses stating that no
+    /// requests remain in the current rate limit window.
+    ///
+    /// # Errors
+    /// - [`GithubError::ScrapeFailure`] final attempt received a 429 or 5xx response
+    /// - [`GithubError::RateLimited`] final attempt received a 403 response with
+    ///   `X-RateLimit-Remaining: 0`
+    /// - See [`GithubError`] for errors related to failing to send the request
+    ///
+    /// Errors are wrapped in [`GithubError::RetriesExhausted`] if the request was
+    /// attempted more than once.
+    ///
+    pub fn send<F>(&self, mut request: F) -> Result<Response, GithubError>
+    where
+        F: FnMut() -> Result<Response, reqwest::Error>
+    {
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+
+            let (error, requested_delay) = match request() {
+                Ok(response) if Self::is_rate_limit_exhausted(&response) => (
+                    GithubError::RateLimited(response.status().to_string()),
+                    Self::requested_delay(response.headers(), Utc::now()),
+                ),
+                Ok(response) if !Self::is_retryable_status(response.status()) => return Ok(response),
+                Ok(response) => (
+                    GithubError::ScrapeFailure(response.status()),
+                    Self::requested_delay(response.headers(), Utc::now()),
+                ),
+                Err(error) => (GithubError::from(error), None),
+            };
+
+            let delay = requested_delay.unwrap_or_else(|| self.backoff(attempts));
+
+            if attempts > self.max_retries || !Self::is_transient(&error) || delay > self.max_delay {
+                return match attempts {
+                    1 => Err(error),
+                    _ => Err(GithubError::RetriesExhausted { attempts, source: Box::new(error) })
+                };
+            }
+
+            thread::sleep(delay);
+        }
+    }
+
+    fn is_retryable_status(status: StatusCode) -> bool {
+        status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+    }
+
+    /// Github signals an exhausted primary rate limit with a 403 response,
+    /// rather than a 429 response.
+    fn is_rate_limit_exhausted(response: &Response) -> bool {
+        response.status() == StatusCode::FORBIDDEN && response.headers()
+            .get(RATE_LIMIT_REMAINING)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|remaining| remaining.trim() == "0")
+    }
+
+    fn is_transient(error: &GithubError) -> bool {
+        matches!(
+            error,
+            GithubError::ScrapeFailure(_)
+                | GithubError::RateLimited(_)
+                | GithubError::Connection(_)
+                | GithubError::Timeout(_)
+        )
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(self.max_delay);
+
+        // "Equal jitter": wait at least half of the exponential delay, plus a
+        // random portion of the remaining half.
+        let half = exponential / 2;
+        half + half.mul_f64(fastrand::f64())
+    }
+
+    fn requested_delay(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+        let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+        if let Some(retry_after) = header(RETRY_AFTER.as_str()) {
+            if let Ok(seconds) = retry_after.trim().parse() {
+                return Some(Duration::from_secs(seconds));
+            }
+
+            let date = DateTime::parse_from_rfc2822(retry_after).ok()?;
+            return Some((date.with_timezone(&Utc) - now).to_std().unwrap_or_default());
+        }
+
+        if header(RATE_LIMIT_REMAINING).is_none_or(|remaining| remaining.trim() == "0") {
+            let reset = header(RATE_LIMIT_RESET)?.trim().parse().ok()?;
+            let reset = DateTime::from_timestamp(reset, 0)?;
+            return Some((reset - now).to_std().unwrap_or_default());
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::blocking::Client;
+    use reqwest::header::HeaderValue;
+    use crate::sources::mock::{serve, MockResponse};
+
+    fn instant_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy { max_retries, base_delay: Duration::ZERO, max_delay: Duration::from_secs(1) }
+    }
+
+    fn headers(values: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in values {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn retries_transient_failures() {
+        let (url, handle) = serve(vec![
+            MockResponse::new(502, "Bad Gateway"),
+            MockResponse::new(429, "Too Many Requests").with_header("Retry-After", "0"),
+            MockResponse::new(200, "OK"),
+        ]);
+
+        let client = Client::new();
+        let response = instant_policy(3).send(|| client.get(&url).send()).unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(handle.join().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn reports_attempts_when_exhausted() {
+        let (url, _) = serve(vec![
+            MockResponse::new(503, "Unavailable"),
+            MockResponse::new(503, "Unavailable"),
+        ]);
+
+        let client = Client::new();
+        let error = instant_policy(1).send(|| client.get(&url).send()).unwrap_err();
+
+        assert!(matches!(
+            error,
+            GithubError::RetriesExhausted { attempts: 2, source }
+                if matches!(*source, GithubError::ScrapeFailure(StatusCode::SERVICE_UNAVAILABLE))
+        ))
+    }
+
+    #[test]
+    fn retries_exhausted_rate_limit_until_reset() {
+        let reset = Utc::now().timestamp().to_string();
+        let (url, handle) = serve(vec![
+            MockResponse::new(403, "Forbidden")
+                .with_header("X-RateLimit-Remaining", "0")
+                .with_header("X-RateLimit-Reset", &reset),
+            MockResponse::new(200, "OK"),
+        ]);
+
+        let client = Client::new();
+        let response = instant_policy(3).send(|| client.get(&url).send()).unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(handle.join().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn reports_exhausted_rate_limit_as_rate_limited() {
+        let reset = (Utc::now().timestamp() + 3600).to_string();
+        let (url, _) = serve(vec![
+            MockResponse::new(403, "Forbidden")
+                .with_header("X-RateLimit-Remaining", "0")
+                .with_header("X-RateLimit-Reset", &reset),
+        ]);
+
+        let client = Client::new();
+        let error = instant_policy(3).send(|| client.get(&url).send()).unwrap_err();
+
+        assert!(matches!(error, GithubError::RateLimited(_)));
+        assert_eq!(error.category(), crate::ErrorCategory::RateLimited);
+    }
+
+    #[test]
+    fn does_not_retry_forbidden_with_remaining_requests() {
+        let (url, handle) = serve(vec![MockResponse::new(403, "Forbidden").with_header("X-RateLimit-Remaining", "10")]);
+
+        let client = Client::new();
+        let response = instant_policy(3).send(|| client.get(&url).send()).unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(handle.join().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn does_not_retry_client_errors() {
+        let (url, handle) = serve(vec![MockResponse::new(404, "Not Found")]);
+
+        let client = Client::new();
+        let response = instant_policy(3).send(|| client.get(&url).send()).unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(handle.join().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn gives_up_if_requested_delay_too_long() {
+        let (url, _) = serve(vec![MockResponse::new(429, "Slow down").with_header("Retry-After", "3600")]);
+
+        let client = Client::new();
+        let error = instant_policy(3).send(|| client.get(&url).send()).unwrap_err();
+
+        assert!(matches!(error, GithubError::ScrapeFailure(StatusCode::TOO_MANY_REQUESTS)))
+    }
+
+    #[test]
+    fn backs_off_exponentially_with_jitter() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+        };
+
+        for (attempt, expected) in [(1, 100), (2, 200), (3, 400), (5, 1000)] {
+            let delay = policy.backoff(attempt);
+            let expected = Duration::from_millis(expected);
+            assert!(delay >= expected / 2 && delay <= expected, "{delay:?} outside of {expected:?}");
+        }
+    }
+
+    #[test]
+    fn reads_retry_after_seconds() {
+        let delay = RetryPolicy::requested_delay(&headers(&[("retry-after", "120")]), Utc::now());
+        assert_eq!(delay, Some(Duration::from_secs(120)))
+    }
+
+    #[test]
+    fn reads_retry_after_date() {
+        let now = DateTime::parse_from_rfc2822("Sun, 18 Sep 2022 10:00:00 GMT").unwrap().with_timezone(&Utc);
+        let delay = RetryPolicy::requested_delay(&headers(&[("retry-after", "Sun, 18 Sep 2022 10:00:30 GMT")]), now);
+
+        assert_eq!(delay, Some(Duration::from_secs(30)))
+    }
+
+    #[test]
+    fn reads_rate_limit_reset() {
+        let now = DateTime::from_timestamp(1_663_495_200, 0).unwrap();
+        let headers = headers(&[(RATE_LIMIT_REMAINING, "0"), (RATE_LIMIT_RESET, "1663495245")]);
+
+        assert_eq!(RetryPolicy::requested_delay(&headers, now), Some(Duration::from_secs(45)))
+    }
+
+    #[test]
+    fn ignores_rate_limit_reset_with_remaining_requests() {
+        let headers = headers(&[(RATE_LIMIT_REMAINING, "10"), (RATE_LIMIT_RESET, "1663495245")]);
+        assert_eq!(RetryPolicy::requested_delay(&headers, Utc::now()), None)
+    }
+}
@@ -0,0 +1,284 @@
+mod streak;
+
+pub use streak::Streak;
+use std::io::{self, Write};
+use chrono::{Datelike, NaiveDate};
+use crate::{Contribution, Heatmap};
+use crate::heatmap::DAYS_IN_WEEK;
+
+const WEEKDAYS: [&str; DAYS_IN_WEEK] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const DATE_FORMAT: &str = "%B %-d, %Y";
+
+/// A `Stats` instance summarizes the contributions of a [`Heatmap`].
+///
+/// Days missing from partial weeks are not counted, so statistics cover
+/// exactly the period spanned by the heatmap.
+///
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+    /// First day covered by the heatmap.
+    pub first_day: Option<NaiveDate>,
+    /// Last day covered by the heatmap.
+    pub last_day: Option<NaiveDate>,
+    /// Number of days covered by the heatmap.
+    pub days: usize,
+    /// Total number of contributions.
+    pub total: usize,
+    /// Number of days with at least one contribution.
+    pub active_days: usize,
+    /// Streak ending on the last day, or on the day before if no contributions
+    /// have been made on the last day yet.
+    pub current_streak: Option<Streak>,
+    /// Longest streak, the earliest one if several are equally long.
+    pub longest_streak: Option<Streak>,
+    /// Day with the most contributions, the earliest one if several are tied.
+    pub busiest_day: Option<Contribution>,
+    /// Mean number of contributions per active day.
+    pub mean_per_active_day: f64,
+    /// Median number of contributions per active day.
+    pub median_per_active_day: f64,
+    /// Total contributions per day of the week, starting on Sunday.
+    pub weekdays: [usize; DAYS_IN_WEEK],
+    /// Total contributions per calendar month, keyed by the first day of the
+    /// month, in chronological order.
+    pub months: Vec<(NaiveDate, usize)>,
+}
+
+impl Stats {
+    /// Computes summary statistics over the contributions of a [`Heatmap`].
+    pub fn from_heatmap(heatmap: &Heatmap) -> Self {
+        let mut days: Vec<&Contribution> = heatmap.days().collect();
+        days.sort_by_key(|day| day.date);
+
+        let mut active: Vec<usize> = days.iter().map(|day| day.count).filter(|count| *count > 0).collect();
+        active.sort_unstable();
+
+        let total = days.iter().map(|day| day.count).sum();
+        let streaks = Self::streaks(&days);
+
+        let mut weekdays = [0; DAYS_IN_WEEK];
+        let mut months: Vec<(NaiveDate, usize)> = vec![];
+
+        for day in &days {
+            weekdays[day.date.weekday().num_days_from_sunday() as usize] += day.count;
+
+            let month = day.date.with_day(1).unwrap();
+            match months.last_mut() {
+                Some((previous, count)) if *previous == month => *count += day.count,
+                _ => months.push((month, day.count)),
+            }
+        }
+
+        Stats {
+            first_day: days.first().map(|day| day.date),
+            last_day: days.last().map(|day| day.date),
+            days: days.len(),
+            total,
+            active_days: active.len(),
+            current_streak: Self::current_streak(&days, &streaks),
+            longest_streak: streaks.iter().copied().max_by_key(|streak| (streak.days(), std::cmp::Reverse(streak.start))),
+            busiest_day: days
+                .iter()
+                .filter(|day| day.count > 0)
+                .max_by_key(|day| (day.count, std::cmp::Reverse(day.date)))
+                .map(|day| (*day).clone()),
+            mean_per_active_day: match active.len() {
+                0 => 0.0,
+                len => total as f64 / len as f64,
+            },
+            median_per_active_day: match active.len() {
+                0 => 0.0,
+                len if len % 2 == 0 => (active[len / 2 - 1] + active[len / 2]) as f64 / 2.0,
+                len => active[len / 2] as f64,
+            },
+            weekdays,
+            months,
+        }
+    }
+
+    /// Describes the statistics as pairs of labels and values, in the order
+    /// they are printed in.
+    pub fn summary(&self) -> Vec<(&'static str, String)> {
+        let describe_streak = |streak: &Option<Streak>| streak
+            .map_or_else(|| "None".to_string(), |streak| streak.describe());
+
+        let period = match (self.first_day, self.last_day) {
+            (Some(first), Some(last)) => format!("{} – {}", first.format(DATE_FORMAT), last.format(DATE_FORMAT)),
+            _ => "None".to_string(),
+        };
+
+        let weekdays = WEEKDAYS
+            .iter()
+            .zip(self.weekdays)
+            .map(|(weekday, count)| format!("{weekday} {count}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let months = self.months
+            .iter()
+            .map(|(month, count)| format!("{} {count}", month.format("%b %Y")))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        vec![
+            ("Period", period),
+            ("Total contributions", self.total.to_string()),
+            ("Active days", format!("{} of {}", self.active_days, self.days)),
+            ("Current streak", describe_streak(&self.current_streak)),
+            ("Longest streak", describe_streak(&self.longest_streak)),
+            ("Busiest day", self.busiest_day
+                .as_ref()
+                .map_or_else(|| "None".to_string(), |day| format!("{} ({})", day.date.format(DATE_FORMAT), day.count))),
+            ("Mean per active day", format!("{:.1}", self.mean_per_active_day)),
+            ("Median per active day", format!("{:.1}", self.median_per_active_day)),
+            ("By weekday", weekdays),
+            ("By month", months),
+        ]
+    }
+
+    /// Writes the [`Stats::summary`] to the provided writer, one aligned
+    /// label and value per line.
+    ///
+    /// # Errors
+    /// Returns an error if writing to the provided writer fails.
+    ///
+    pub fn render_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        let summary = self.summary();
+        let width = summary.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+
+        for (label, value) in summary {
+            writeln!(writer, "{label:width$}  {value}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Splits days into runs of consecutive dates with at least one contribution.
+    fn streaks(days: &[&Contribution]) -> Vec<Streak> {
+        let mut streaks: Vec<Streak> = vec![];
+
+        for day in days.iter().filter(|day| day.count > 0) {
+            match streaks.last_mut() {
+                Some(streak) if day.date.pred_opt() == Some(streak.end) => streak.end = day.date,
+                _ => streaks.push(Streak { start: day.date, end: day.date }),
+            }
+        }
+
+        streaks
+    }
+
+    fn current_streak(days: &[&Contribution], streaks: &[Streak]) -> Option<Streak> {
+        let last_day = days.last()?.date;
+        let streak = *streaks.last()?;
+
+        match last_day - streak.end {
+            gap if gap.num_days() <= 1 => Some(streak),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContributionWeek;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    /// Builds a heatmap of consecutive days starting on Sunday, September 25, 2022.
+    fn heatmap(counts: &[usize]) -> Heatmap {
+        let start = date("2022-09-25");
+        let contribution_weeks = counts
+            .chunks(7)
+            .enumerate()
+            .map(|(week, counts)| {
+                let mut contributions: Vec<_> = counts
+                    .iter()
+                    .enumerate()
+                    .map(|(day, count)| Some(Contribution {
+                        date: start + chrono::Duration::days((week * 7 + day) as i64),
+                        heat_level: (*count).min(4),
+                        count: *count,
+                    }))
+                    .collect();
+                contributions.resize(7, None);
+
+                ContributionWeek { contributions }
+            })
+            .collect();
+
+        Heatmap { contribution_weeks }
+    }
+
+    #[test]
+    fn computes_totals() {
+        let stats = Stats::from_heatmap(&heatmap(&[0, 3, 1, 0, 8, 2, 0, 4, 0]));
+
+        assert_eq!(stats.first_day, Some(date("2022-09-25")));
+        assert_eq!(stats.last_day, Some(date("2022-10-03")));
+        assert_eq!(stats.days, 9);
+        assert_eq!(stats.total, 18);
+        assert_eq!(stats.active_days, 5);
+        assert_eq!(stats.busiest_day.map(|day| day.date), Some(date("2022-09-29")));
+        assert_eq!(stats.mean_per_active_day, 3.6);
+        assert_eq!(stats.median_per_active_day, 3.0);
+    }
+
+    #[test]
+    fn computes_distributions() {
+        let stats = Stats::from_heatmap(&heatmap(&[0, 3, 1, 0, 8, 2, 0, 4, 0]));
+
+        assert_eq!(stats.weekdays, [4, 3, 1, 0, 8, 2, 0]);
+        assert_eq!(stats.months, vec![(date("2022-09-01"), 14), (date("2022-10-01"), 4)]);
+    }
+
+    #[test]
+    fn computes_streaks() {
+        let stats = Stats::from_heatmap(&heatmap(&[1, 1, 0, 2, 2, 2, 0, 5, 1]));
+
+        assert_eq!(stats.longest_streak, Some(Streak { start: date("2022-09-28"), end: date("2022-09-30") }));
+        assert_eq!(stats.current_streak, Some(Streak { start: date("2022-10-02"), end: date("2022-10-03") }));
+    }
+
+    #[test]
+    fn current_streak_survives_last_day_without_contributions() {
+        let ongoing = Stats::from_heatmap(&heatmap(&[1, 1, 0]));
+        let broken = Stats::from_heatmap(&heatmap(&[1, 1, 0, 0]));
+
+        assert_eq!(ongoing.current_streak, Some(Streak { start: date("2022-09-25"), end: date("2022-09-26") }));
+        assert_eq!(broken.current_streak, None);
+    }
+
+    #[test]
+    fn computes_median_of_even_number_of_active_days() {
+        let stats = Stats::from_heatmap(&heatmap(&[1, 2, 6, 9]));
+        assert_eq!(stats.median_per_active_day, 4.0);
+    }
+
+    #[test]
+    fn handles_heatmap_without_contributions() {
+        let stats = Stats::from_heatmap(&heatmap(&[0, 0, 0]));
+
+        assert_eq!(stats.total, 0);
+        assert_eq!(stats.longest_streak, None);
+        assert_eq!(stats.current_streak, None);
+        assert_eq!(stats.busiest_day, None);
+        assert_eq!(stats.mean_per_active_day, 0.0);
+    }
+
+    #[test]
+    fn renders_aligned_summary() {
+        let stats = Stats::from_heatmap(&heatmap(&[0, 3, 1]));
+        let mut output = vec![];
+        stats.render_to(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("Total contributions    4\n"));
+        assert!(output.contains("Longest streak         2 days, September 26, 2022 – September 27, 2022\n"));
+        assert!(output.contains("By weekday             Sun 0, Mon 3, Tue 1, Wed 0, Thu 0, Fri 0, Sat 0\n"));
+        assert!(output.contains("By month               Sep 2022 4\n"));
+    }
+}
@@ -0,0 +1,44 @@
+use chrono::NaiveDate;
+
+/// A `Streak` is a run of consecutive days on which at least one contribution
+/// was made.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Streak {
+    /// First day of the streak.
+    pub start: NaiveDate,
+    /// Last day of the streak.
+    pub end: NaiveDate,
+}
+
+impl Streak {
+    /// Returns the number of days spanned by the streak, including both ends.
+    pub fn days(&self) -> usize {
+        (self.end - self.start).num_days() as usize + 1
+    }
+
+    /// Describes the streak, e.g. "3 days, September 15, 2022 – September 17, 2022".
+    pub fn describe(&self) -> String {
+        let days = match self.days() {
+            1 => "1 day".to_string(),
+            days => format!("{days} days"),
+        };
+
+        format!("{days}, {} – {}", self.start.format("%B %-d, %Y"), self.end.format("%B %-d, %Y"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_streak() {
+        let start = NaiveDate::from_ymd_opt(2022, 9, 15).unwrap();
+        let streak = Streak { start, end: NaiveDate::from_ymd_opt(2022, 9, 17).unwrap() };
+
+        assert_eq!(streak.days(), 3);
+        assert_eq!(streak.describe(), "3 days, September 15, 2022 – September 17, 2022");
+        assert_eq!(Streak { start, end: start }.describe(), "1 day, September 15, 2022 – September 15, 2022");
+    }
+}
@@ -1,21 +1,233 @@
-use super::parsers::{parse_slug, parse_year};
+use super::parsers::{parse_date, parse_glyph_ramp, parse_hex_color, parse_palette, parse_slug, parse_year};
+use crate::{ArgumentError, DateRange, GlyphRamp, Palette};
+use chrono::NaiveDate;
+use std::{ops::RangeInclusive, path::PathBuf};
 use clap::{Parser, ValueEnum};
 
+/// Label of the heatmap rendered from `--input` when no profile slug is provided.
+pub const INPUT_LABEL: &str = "input";
+
 /// Scrapes a Github profile, and generates a contributions heatmap in Unicode
 #[derive(Parser, Debug)]
 #[clap(author, version, about)]
 pub struct Args {
-    /// Github profile slug, e.g. adenh93
-    #[clap(value_parser = parse_slug)]
-    pub slug: String,
+    /// Github profile slugs, e.g. adenh93. Several profiles are drawn beneath each other for comparison.
+    /// Optional with --input, where the slug only labels the heatmap
+    #[clap(value_parser = parse_slug, value_name = "SLUG", required_unless_present_any = &["team-file", "input"])]
+    pub slugs: Vec<String>,
+
+    /// Combine the heatmaps of all profiles into a single team heatmap, summing daily contributions
+    #[clap(long)]
+    pub team: bool,
+
+    /// File listing the profile slugs of team members, one per line. Use - for standard input. Implies --team
+    #[clap(long, value_name = "PATH")]
+    pub team_file: Option<PathBuf>,
+
+    /// Compare two profiles, or one profile in the two years provided by --years, drawing which was more active each day
+    #[clap(long, conflicts_with_all = &["team", "team-file", "all-years"])]
+    pub diff: bool,
 
     /// Heatmap color scheme. Nodes will be shaded depending on heat level.
     #[clap(short, long, value_enum, default_value_t = ColorValues::Green)]
     pub color: ColorValues,
 
-    /// Specific year to fetch contributions
-    #[clap(short, long, value_parser = parse_year)]
-    pub year: Option<String>
+    /// Custom heatmap palette, overriding --color. Either a preset (github-light, github-dark,
+    /// halloween, viridis) or five comma-separated hex colors, from no contributions to most
+    #[clap(short, long, value_parser = parse_palette, conflicts_with = "color")]
+    pub palette: Option<Palette>,
+
+    /// Color depth of the terminal grid. Detected from NO_COLOR, COLORTERM and TERM by default,
+    /// terminals without color support shade nodes with glyphs instead
+    #[clap(long, value_enum, default_value_t = ColorModeValues::Auto)]
+    pub color_mode: ColorModeValues,
+
+    /// Shade the terminal grid with glyphs of increasing density instead of color, e.g. " ░▒▓█"
+    #[clap(long, value_parser = parse_glyph_ramp)]
+    pub glyphs: Option<GlyphRamp>,
+
+    /// Output format. The grid is drawn in the terminal, other formats print the heatmap data
+    #[clap(short, long, value_enum, default_value_t = FormatValues::Grid)]
+    pub format: FormatValues,
+
+    /// File to write the output to instead of standard output
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Hide the month labels above the terminal grid
+    #[clap(long, help_heading = "GRID OPTIONS")]
+    pub no_month_labels: bool,
+
+    /// Hide the Mon/Wed/Fri labels left of the terminal grid
+    #[clap(long, help_heading = "GRID OPTIONS")]
+    pub no_weekday_labels: bool,
+
+    /// Hide the legend below the terminal grid
+    #[clap(long, help_heading = "GRID OPTIONS")]
+    pub no_legend: bool,
+
+    /// Print summary statistics below the terminal grid, e.g. total contributions and streaks
+    #[clap(long, help_heading = "GRID OPTIONS")]
+    pub stats: bool,
+
+    /// Width and height of each heatmap node in PNG output, in pixels
+    #[clap(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..), help_heading = "PNG OPTIONS")]
+    pub cell_size: u32,
+
+    /// Space between adjacent heatmap nodes in PNG output, in pixels
+    #[clap(long, default_value_t = 3, help_heading = "PNG OPTIONS")]
+    pub cell_gap: u32,
+
+    /// Space around the heatmap in PNG output, in pixels
+    #[clap(long, default_value_t = 10, help_heading = "PNG OPTIONS")]
+    pub padding: u32,
+
+    /// Background color of SVG, PNG and HTML output, e.g. #ffffff
+    #[clap(long, value_parser = parse_hex_color, default_value = "#0d1117")]
+    pub background: (u8, u8, u8),
+
+    /// Years to fetch contributions for, e.g. 2022, a range such as 2019..2024, or a list such as 2019,2021
+    #[clap(short, long, visible_alias = "years", value_name = "YEARS", value_parser = parse_year, value_delimiter = ',')]
+    pub year: Option<Vec<RangeInclusive<i32>>>,
+
+    /// Fetch contributions for every year the profile has been active in
+    #[clap(long, conflicts_with_all = &["year", "from", "to"])]
+    pub all_years: bool,
+
+    /// Layout of heatmaps spanning multiple years in the terminal grid: one grid per year, or one continuous grid
+    #[clap(long, value_enum, default_value_t = LayoutValues::Stacked, help_heading = "GRID OPTIONS")]
+    pub layout: LayoutValues,
+
+    /// First day to fetch contributions for, e.g. 2023-04-01. Requires --to
+    #[clap(long, value_parser = parse_date, requires = "to", conflicts_with = "year")]
+    pub from: Option<NaiveDate>,
+
+    /// Last day to fetch contributions for, e.g. 2024-03-31. Requires --from
+    #[clap(long, value_parser = parse_date, requires = "from", conflicts_with = "year")]
+    pub to: Option<NaiveDate>,
+
+    /// Backend used to fetch contributions. The GraphQL API requires a personal access token.
+    #[clap(short, long, value_enum, default_value_t = SourceValues::Html)]
+    pub source: SourceValues,
+
+    /// Github personal access token used to authenticate against the GraphQL API
+    #[clap(long, env = "GITHUB_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+
+    /// Saved Github profile page (or calendar fragment) to render instead of fetching
+    /// contributions. Use '-' to read from standard input.
+    #[clap(short, long, conflicts_with = "source")]
+    pub input: Option<PathBuf>,
+
+    /// Maximum number of retries after transient failures (429 and 5xx responses, timeouts)
+    #[clap(long, default_value_t = 3)]
+    pub retries: u32,
+
+    /// Initial delay in milliseconds before retrying, doubled after every attempt
+    #[clap(long, default_value_t = 500)]
+    pub retry_delay: u64,
+
+    /// Number of seconds that cached profile pages are used for before being revalidated
+    #[clap(long, default_value_t = 900)]
+    pub cache_ttl: u64,
+
+    /// Disable the on-disk cache of profile pages
+    #[clap(long)]
+    pub no_cache: bool,
+
+    /// Ignore cached profile pages, fetching and caching them again
+    #[clap(long, conflicts_with = "no-cache")]
+    pub refresh: bool,
+
+    /// Format used when reporting errors on standard error
+    #[clap(long, value_enum, default_value_t = ErrorFormatValues::Text)]
+    pub error_format: ErrorFormatValues,
+}
+
+impl Args {
+    /// Determines the range of dates to fetch contributions for, from either
+    /// `--year` or `--from` and `--to`. Multiple years are spanned by a single
+    /// range, from the 1st of January of the first year until the 31st of
+    /// December of the last year.
+    ///
+    /// Returns `None` if no range was requested, in which case contributions
+    /// are fetched for the source's default period.
+    ///
+    /// # Errors
+    /// Returns an error if `--from` falls after `--to`.
+    ///
+    pub fn date_range(&self) -> Result<Option<DateRange>, ArgumentError> {
+        match (self.years(), self.from, self.to) {
+            (Some(years), _, _) => Ok(DateRange::spanning_years(&years)),
+            (None, Some(from), Some(to)) if from > to => Err(ArgumentError::ReversedDateRange { from, to }),
+            (None, Some(from), Some(to)) => Ok(Some(DateRange { from, to })),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the profile slugs to fetch contributions for. A heatmap read
+    /// from `--input` without a slug is labelled [`INPUT_LABEL`] instead.
+    pub fn profile_slugs(&self) -> Vec<String> {
+        match (&self.input, self.slugs.is_empty()) {
+            (Some(_), true) => vec![INPUT_LABEL.to_string()],
+            _ => self.slugs.clone(),
+        }
+    }
+
+    /// Returns the years selected by `--year`, with ranges expanded, in
+    /// ascending order and without duplicates.
+    pub fn years(&self) -> Option<Vec<i32>> {
+        let mut years: Vec<i32> = self.year.as_ref()?.iter().cloned().flatten().collect();
+        years.sort_unstable();
+        years.dedup();
+
+        Some(years)
+    }
+
+    /// Checks combinations of arguments which cannot be expressed as Clap
+    /// constraints: the date range of [`Args::date_range`], the two heatmaps
+    /// compared by `--diff`, and lists of years with gaps.
+    ///
+    /// Years with gaps, e.g. `2019,2021`, are only supported when each year is
+    /// drawn separately: as stacked grids of a single profile, or as the two
+    /// sides of a diff. Other modes draw a single continuous range.
+    ///
+    /// # Errors
+    /// Returns an error if `--from` falls after `--to`, if `--diff` is provided
+    /// with neither two slugs, nor one slug and two years, or if the years have
+    /// gaps in a mode that requires a continuous range.
+    ///
+    pub fn validate(&self) -> Result<(), ArgumentError> {
+        self.date_range()?;
+
+        let years = self.years();
+        let team = self.team || self.team_file.is_some();
+        let slugs = self.profile_slugs().len();
+
+        match (self.diff, slugs, years.as_ref().map(Vec::len)) {
+            (false, _, _) | (true, 2, _) | (true, 1, Some(2)) => {},
+            _ => return Err(ArgumentError::DiffOperands),
+        }
+
+        let separate = match (self.diff, slugs, &self.format, &self.layout) {
+            (true, 1, _, _) => true,
+            (false, 1, FormatValues::Grid, LayoutValues::Stacked) => !team,
+            _ => false,
+        };
+
+        match years {
+            Some(years) if !separate && years.windows(2).any(|pair| pair[1] != pair[0] + 1) => {
+                Err(ArgumentError::NonConsecutiveYears)
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone)]
+pub enum LayoutValues {
+    Stacked,
+    Timeline,
 }
 
 #[derive(ValueEnum, Debug, Clone)]
@@ -24,3 +236,37 @@ pub enum ColorValues {
     Green,
     Blue,
 }
+
+#[derive(ValueEnum, Debug, Clone)]
+pub enum ColorModeValues {
+    Auto,
+    Truecolor,
+    #[clap(name = "256")]
+    Ansi256,
+    #[clap(name = "16")]
+    Ansi16,
+    None,
+}
+
+#[derive(ValueEnum, Debug, Clone)]
+pub enum FormatValues {
+    Grid,
+    Json,
+    Csv,
+    Tsv,
+    Svg,
+    Png,
+    Html,
+}
+
+#[derive(ValueEnum, Debug, Clone)]
+pub enum SourceValues {
+    Html,
+    Graphql,
+}
+
+#[derive(ValueEnum, Debug, Clone)]
+pub enum ErrorFormatValues {
+    Text,
+    Json,
+}
@@ -1,14 +1,86 @@
+use std::{error, io};
+use chrono::NaiveDate;
 use thiserror::Error;
 use reqwest::StatusCode;
+use serde_json::json;
+
+/// A stable category assigned to every error variant, each corresponding to a
+/// documented process exit code.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ErrorCategory {
+    /// Any error not belonging to another category. Exits with code 1.
+    Unknown = 1,
+    /// The provided command line arguments are invalid. Exits with code 2.
+    Arguments = 2,
+    /// The requested Github profile does not exist. Exits with code 3.
+    ProfileNotFound = 3,
+    /// Github is rate limiting requests. Exits with code 4.
+    RateLimited = 4,
+    /// Github responded with an unexpected status or API error. Exits with code 5.
+    ServiceError = 5,
+    /// Github could not be reached due to a network failure. Exits with code 6.
+    Network = 6,
+    /// The provided personal access token is missing or invalid. Exits with code 7.
+    Authentication = 7,
+    /// The scraped markup or API response no longer matches the expected format,
+    /// usually caused by an update to Github. Exits with code 10.
+    MarkupChanged = 10,
+    /// A local input file could not be read. Exits with code 11.
+    Input = 11,
+    /// The heatmap could not be written in the requested output format. Exits with code 12.
+    Output = 12,
+}
+
+impl ErrorCategory {
+    /// Returns the process exit code corresponding to the category.
+    pub fn exit_code(self) -> i32 {
+        self as i32
+    }
+
+    /// Returns a machine-readable name for the category.
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorCategory::Unknown => "unknown",
+            ErrorCategory::Arguments => "arguments",
+            ErrorCategory::ProfileNotFound => "profile_not_found",
+            ErrorCategory::RateLimited => "rate_limited",
+            ErrorCategory::ServiceError => "service_error",
+            ErrorCategory::Network => "network",
+            ErrorCategory::Authentication => "authentication",
+            ErrorCategory::MarkupChanged => "markup_changed",
+            ErrorCategory::Input => "input",
+            ErrorCategory::Output => "output",
+        }
+    }
+}
 
 /// A collection of error variants related to making a request
 /// to a Github profile page prior to scraping.
-#[derive(Error, Debug, Eq, PartialEq)]
+#[derive(Error, Debug)]
 pub enum GithubError {
     /// Represents a generic failure while trying to make a GET request
     /// to the specified Github profile page.
     #[error("Unable to reach Github services. Try again later!")]
-    BadRequest,
+    BadRequest(#[source] reqwest::Error),
+
+    /// Represents a failure to establish a connection to Github services, e.g.
+    /// a DNS resolution failure or a refused connection.
+    #[error("Unable to connect to Github services. Check your network connection!")]
+    Connection(#[source] reqwest::Error),
+
+    /// Represents a request to Github services which did not complete in time.
+    #[error("Request to Github services timed out. Try again later!")]
+    Timeout(#[source] reqwest::Error),
+
+    /// Represents a failure to negotiate a secure connection with Github services,
+    /// e.g. an invalid certificate or a failed TLS handshake.
+    #[error("Unable to establish a secure connection to Github services.")]
+    Tls(#[source] reqwest::Error),
+
+    /// Represents a failure to read or decode the body of a response from Github
+    /// services.
+    #[error("Unable to decode response from Github services.")]
+    Decode(#[source] reqwest::Error),
     
     /// Represents a failure caused while attempting to scrape the 
     /// specified Github profile page. This is typically caused by either
@@ -20,6 +92,117 @@ pub enum GithubError {
     /// Github profile page.
     #[error("Unable to find Github profile at url: '{0}'")]
     ProfileNotFound(String),
+
+    /// Represents an attempt to query the Github GraphQL API without providing
+    /// a personal access token.
+    #[error("A Github personal access token is required. Provide one with --token or GITHUB_TOKEN.")]
+    MissingToken,
+
+    /// Represents a 401 response caused by querying the Github GraphQL API with
+    /// an invalid, expired or revoked personal access token.
+    #[error("Github rejected the provided personal access token.")]
+    Unauthorized,
+
+    /// Represents a GraphQL error payload, or a 403 response with no requests
+    /// remaining, stating that the Github rate limit has been exceeded.
+    #[error("Github API rate limit exceeded: '{0}'")]
+    RateLimited(String),
+
+    /// Represents any other GraphQL error payload returned by the Github GraphQL API.
+    #[error("Github API query failed with error '{kind}': '{message}'")]
+    GraphQlQuery {
+        /// Error type reported by the GraphQL API, e.g. `FORBIDDEN`.
+        kind: String,
+        /// Human-readable error message reported by the GraphQL API.
+        message: String
+    },
+
+    /// Represents a response from the Github GraphQL API which does not match the
+    /// shape of the queried contribution calendar.
+    #[error("Unexpected response from Github API: {0}")]
+    UnexpectedResponse(String),
+
+    /// Represents a request which kept failing after being retried, wrapping the
+    /// error encountered during the final attempt.
+    #[error("{source} (gave up after {attempts} attempts)")]
+    RetriesExhausted {
+        /// Number of attempts made, including the initial request.
+        attempts: u32,
+        /// Error encountered during the final attempt.
+        source: Box<GithubError>
+    },
+}
+
+impl GithubError {
+    /// Returns the [`ErrorCategory`] that the error variant belongs to.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            GithubError::BadRequest(_)
+            | GithubError::Connection(_)
+            | GithubError::Timeout(_)
+            | GithubError::Tls(_)
+            | GithubError::Decode(_) => ErrorCategory::Network,
+            GithubError::ScrapeFailure(StatusCode::TOO_MANY_REQUESTS)
+            | GithubError::RateLimited(_) => ErrorCategory::RateLimited,
+            GithubError::ScrapeFailure(_)
+            | GithubError::GraphQlQuery { .. } => ErrorCategory::ServiceError,
+            GithubError::ProfileNotFound(_) => ErrorCategory::ProfileNotFound,
+            GithubError::MissingToken
+            | GithubError::Unauthorized => ErrorCategory::Authentication,
+            GithubError::UnexpectedResponse(_) => ErrorCategory::MarkupChanged,
+            GithubError::RetriesExhausted { source, .. } => source.category(),
+        }
+    }
+
+    /// Returns a machine-readable name for the error variant.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GithubError::BadRequest(_) => "bad_request",
+            GithubError::Connection(_) => "connection",
+            GithubError::Timeout(_) => "timeout",
+            GithubError::Tls(_) => "tls",
+            GithubError::Decode(_) => "decode",
+            GithubError::ScrapeFailure(_) => "scrape_failure",
+            GithubError::ProfileNotFound(_) => "profile_not_found",
+            GithubError::MissingToken => "missing_token",
+            GithubError::Unauthorized => "unauthorized",
+            GithubError::RateLimited(_) => "rate_limited",
+            GithubError::GraphQlQuery { .. } => "graphql_query",
+            GithubError::UnexpectedResponse(_) => "unexpected_response",
+            GithubError::RetriesExhausted { .. } => "retries_exhausted",
+        }
+    }
+}
+
+impl From<reqwest::Error> for GithubError {
+    fn from(error: reqwest::Error) -> Self {
+        if is_tls_error(&error) {
+            GithubError::Tls(error)
+        } else if error.is_timeout() {
+            GithubError::Timeout(error)
+        } else if error.is_connect() {
+            GithubError::Connection(error)
+        } else if error.is_decode() || error.is_body() {
+            GithubError::Decode(error)
+        } else {
+            GithubError::BadRequest(error)
+        }
+    }
+}
+
+fn is_tls_error(error: &reqwest::Error) -> bool {
+    // TLS failures surface as connection errors, so the underlying source chain is
+    // walked to find out whether the TLS backend was the root cause.
+    let mut source = error::Error::source(error);
+
+    while let Some(err) = source {
+        if err.is::<native_tls::Error>() {
+            return true;
+        }
+        source = err.source();
+    }
+
+    false
 }
 
 /// A collection of error variants related to parsing a Github contribution
@@ -61,5 +244,399 @@ pub enum HeatmapError {
     /// to the Github profile page. Elements are typically distanced either 13px or 15px
     /// depending on the density of the profile page (contains README.md, etc).
     #[error("Failed to parse Heatmap nodes. Unknown node size scraped from Github frontend.")]
-    UnknownNodeFormat
+    UnknownNodeFormat,
+
+    /// Represents a failure to parse the number of contributions from the tooltip
+    /// text accompanying a heatmap node, e.g. "4 contributions on Sunday, September 18, 2022".
+    /// This is usually caused by an update to the Github front end.
+    #[error("Failed to parse contribution count from text: '{0}'")]
+    ParseContributionCount(String),
+}
+
+impl HeatmapError {
+    /// Returns the [`ErrorCategory`] that the error variant belongs to. All
+    /// variants indicate that the Github front end has changed.
+    pub fn category(&self) -> ErrorCategory {
+        ErrorCategory::MarkupChanged
+    }
+
+    /// Returns a machine-readable name for the error variant.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            HeatmapError::QueryElement { .. } => "query_element",
+            HeatmapError::QueryAttribute { .. } => "query_attribute",
+            HeatmapError::ParseAttribute { .. } => "parse_attribute",
+            HeatmapError::UnknownNodeFormat => "unknown_node_format",
+            HeatmapError::ParseContributionCount(_) => "parse_contribution_count",
+        }
+    }
+}
+
+/// A collection of error variants related to reading a previously saved Github
+/// profile page or a team file from disk or standard input.
+#[derive(Error, Debug)]
+pub enum InputError {
+    /// Represents a failure to read the provided input file, e.g. because it
+    /// does not exist or is not valid UTF-8.
+    #[error("Unable to read input '{path}': {source}")]
+    Read {
+        /// Path of the input file, or `-` for standard input.
+        path: String,
+        /// Underlying I/O error.
+        source: io::Error
+    },
+
+    /// Represents a team file containing an invalid profile slug.
+    #[error("Invalid team file '{path}': {message}")]
+    InvalidSlug {
+        /// Path of the team file, or `-` for standard input.
+        path: String,
+        /// Description of the invalid slug and the line it was found on.
+        message: String
+    },
+}
+
+impl InputError {
+    /// Returns the [`ErrorCategory`] that the error variant belongs to.
+    pub fn category(&self) -> ErrorCategory {
+        ErrorCategory::Input
+    }
+
+    /// Returns a machine-readable name for the error variant.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            InputError::Read { .. } => "input_read",
+            InputError::InvalidSlug { .. } => "input_invalid_slug",
+        }
+    }
+}
+
+/// A collection of error variants related to writing a heatmap in one of the
+/// supported output formats.
+#[derive(Error, Debug)]
+pub enum OutputError {
+    /// Represents an output format which is unavailable in the current build,
+    /// e.g. due to a disabled cargo feature.
+    #[error("Unsupported output format: {0}")]
+    Unsupported(String),
+
+    /// Represents a failure to create the output file, e.g. because its parent
+    /// directory does not exist.
+    #[error("Unable to create output file '{path}': {source}")]
+    Create {
+        /// Path of the output file.
+        path: String,
+        /// Underlying I/O error.
+        source: io::Error
+    },
+
+    /// Represents a failure to write the formatted heatmap to its destination.
+    #[error("Unable to write output: {0}")]
+    Write(#[source] io::Error),
+
+    /// Represents image layout options which would produce an empty image, or
+    /// one too large to be rasterized.
+    #[error("Invalid image dimensions: {0}")]
+    ImageDimensions(String),
+}
+
+impl OutputError {
+    /// Returns the [`ErrorCategory`] that the error variant belongs to.
+    pub fn category(&self) -> ErrorCategory {
+        ErrorCategory::Output
+    }
+
+    /// Returns a machine-readable name for the error variant.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            OutputError::Unsupported(_) => "output_unsupported",
+            OutputError::Create { .. } => "output_create",
+            OutputError::Write(_) => "output_write",
+            OutputError::ImageDimensions(_) => "output_image_dimensions",
+        }
+    }
+}
+
+/// A collection of error variants related to comparing the heatmaps of
+/// several profiles at once.
+#[derive(Error, Debug)]
+pub enum CompareError {
+    /// Represents a failure to fetch the heatmaps of some of the compared
+    /// profiles or team members. When comparing profiles, the heatmaps of the
+    /// remaining profiles are still written.
+    #[error("Failed to fetch contributions for {}: {source}", .slugs.join(", "))]
+    Incomplete {
+        /// Slugs of the profiles which could not be fetched.
+        slugs: Vec<String>,
+        /// Error encountered while fetching the first of these profiles.
+        source: Box<dyn error::Error + Send + Sync>
+    },
+}
+
+impl CompareError {
+    /// Returns the [`ErrorCategory`] that the error variant belongs to, which
+    /// is the category of the underlying error.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            CompareError::Incomplete { source, .. } => ErrorReport::new(source.as_ref()).category,
+        }
+    }
+
+    /// Returns a machine-readable name for the error variant.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CompareError::Incomplete { .. } => "compare_incomplete",
+        }
+    }
+}
+
+/// A collection of error variants related to combinations of command line
+/// arguments which cannot be checked while parsing them.
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum ArgumentError {
+    /// Represents a `--from` date falling after the `--to` date.
+    #[error("--from {from} must not be after --to {to}")]
+    ReversedDateRange {
+        /// First day of the requested range.
+        from: NaiveDate,
+        /// Last day of the requested range.
+        to: NaiveDate
+    },
+
+    /// Represents `--diff` provided without exactly two heatmaps to compare.
+    #[error("--diff requires two slugs, or one slug and two --years")]
+    DiffOperands,
+
+    /// Represents a list of years with gaps, e.g. `2019,2021`, in a mode which
+    /// draws a single continuous range.
+    #[error("--years must be consecutive unless each year is drawn as a stacked grid of a single profile")]
+    NonConsecutiveYears,
+}
+
+impl ArgumentError {
+    /// Returns the [`ErrorCategory`] that the error variant belongs to.
+    pub fn category(&self) -> ErrorCategory {
+        ErrorCategory::Arguments
+    }
+
+    /// Returns a machine-readable name for the error variant.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ArgumentError::ReversedDateRange { .. } => "reversed_date_range",
+            ArgumentError::DiffOperands => "diff_operands",
+            ArgumentError::NonConsecutiveYears => "non_consecutive_years",
+        }
+    }
+}
+
+/// A summary of an error returned by [`crate::run`], used to report the error
+/// to the user and to decide on the process exit code.
+#[derive(Debug, Eq, PartialEq)]
+pub struct ErrorReport {
+    /// Machine-readable name of the error variant, e.g. `timeout`.
+    pub kind: &'static str,
+    /// Category of the error, determining the exit code.
+    pub category: ErrorCategory,
+    /// Human-readable error message.
+    pub message: String,
+    /// Messages of the underlying errors which caused the error, if any.
+    pub causes: Vec<String>,
+}
+
+impl ErrorReport {
+    /// Constructs a new `ErrorReport` instance from any error, categorising
+    /// [`GithubError`], [`HeatmapError`], [`InputError`], [`OutputError`],
+    /// [`CompareError`] and [`ArgumentError`] variants, as well as Clap parsing
+    /// errors.
+    pub fn new(error: &(dyn error::Error + 'static)) -> Self {
+        let (kind, category) = if let Some(err) = error.downcast_ref::<GithubError>() {
+            (err.kind(), err.category())
+        } else if let Some(err) = error.downcast_ref::<HeatmapError>() {
+            (err.kind(), err.category())
+        } else if let Some(err) = error.downcast_ref::<InputError>() {
+            (err.kind(), err.category())
+        } else if let Some(err) = error.downcast_ref::<OutputError>() {
+            (err.kind(), err.category())
+        } else if let Some(err) = error.downcast_ref::<CompareError>() {
+            (err.kind(), err.category())
+        } else if let Some(err) = error.downcast_ref::<ArgumentError>() {
+            (err.kind(), err.category())
+        } else if error.is::<clap::Error>() {
+            ("invalid_arguments", ErrorCategory::Arguments)
+        } else {
+            ("unknown", ErrorCategory::Unknown)
+        };
+
+        // Clap errors are followed by usage instructions, which are left out.
+        let message = match error.downcast_ref::<clap::Error>() {
+            Some(err) => err.to_string().lines().next().unwrap_or_default().trim_start_matches("error: ").to_string(),
+            None => error.to_string(),
+        };
+
+        let mut causes = vec![];
+        let mut source = error.source();
+
+        while let Some(err) = source {
+            causes.push(err.to_string());
+            source = err.source();
+        }
+
+        ErrorReport { kind, category, message, causes }
+    }
+
+    /// Returns the process exit code corresponding to the error.
+    pub fn exit_code(&self) -> i32 {
+        self.category.exit_code()
+    }
+
+    /// Serializes the report as a single line JSON object.
+    pub fn to_json(&self) -> String {
+        json!({
+            "kind": self.kind,
+            "category": self.category.name(),
+            "exit_code": self.exit_code(),
+            "message": self.message,
+            "causes": self.causes,
+        }).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{net::TcpListener, thread, time::Duration};
+    use reqwest::blocking::Client;
+
+    fn unused_address() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    }
+
+    #[test]
+    fn categorises_errors() {
+        assert_eq!(GithubError::ProfileNotFound("url".to_string()).category().exit_code(), 3);
+        assert_eq!(GithubError::ScrapeFailure(StatusCode::TOO_MANY_REQUESTS).category().exit_code(), 4);
+        assert_eq!(GithubError::ScrapeFailure(StatusCode::BAD_GATEWAY).category().exit_code(), 5);
+        assert_eq!(GithubError::Unauthorized.category().exit_code(), 7);
+        assert_eq!(HeatmapError::UnknownNodeFormat.category().exit_code(), 10);
+    }
+
+    #[test]
+    fn reports_categorised_error() {
+        let error = HeatmapError::UnknownNodeFormat;
+        let report = ErrorReport::new(&error);
+
+        assert_eq!(report, ErrorReport {
+            kind: "unknown_node_format",
+            category: ErrorCategory::MarkupChanged,
+            message: error.to_string(),
+            causes: vec![],
+        })
+    }
+
+    #[test]
+    fn reports_error_causes() {
+        let error = InputError::Read {
+            path: "profile.html".to_string(),
+            source: io::Error::new(io::ErrorKind::NotFound, "missing")
+        };
+        let report = ErrorReport::new(&error);
+
+        assert_eq!(report.exit_code(), 11);
+        assert_eq!(report.causes, vec!["missing".to_string()]);
+    }
+
+    #[test]
+    fn reports_incomplete_comparison() {
+        let error = CompareError::Incomplete {
+            slugs: vec!["alice".to_string(), "bob".to_string()],
+            source: Box::new(GithubError::ProfileNotFound("https://github.com/alice".to_string())),
+        };
+        let report = ErrorReport::new(&error);
+
+        assert_eq!(report.kind, "compare_incomplete");
+        assert_eq!(
+            report.message,
+            "Failed to fetch contributions for alice, bob: Unable to find Github profile at url: 'https://github.com/alice'"
+        );
+        assert_eq!(report.exit_code(), 3);
+    }
+
+    #[test]
+    fn reports_argument_error() {
+        let report = ErrorReport::new(&ArgumentError::DiffOperands);
+
+        assert_eq!(report.kind, "diff_operands");
+        assert_eq!(report.category.name(), "arguments");
+        assert_eq!(report.exit_code(), 2);
+    }
+
+    #[test]
+    fn reports_clap_error() {
+        use clap::Parser;
+
+        let error = crate::Args::try_parse_from(["github-heatmap", "torvalds", "--year", "22"]).unwrap_err();
+        let report = ErrorReport::new(&error);
+
+        assert_eq!(report.kind, "invalid_arguments");
+        assert_eq!(report.exit_code(), 2);
+        assert!(report.message.starts_with("Invalid value \"22\""), "{}", report.message);
+    }
+
+    #[test]
+    fn reports_unknown_error() {
+        let error = "abc".parse::<i32>().unwrap_err();
+        let report = ErrorReport::new(&error);
+
+        assert_eq!(report.category, ErrorCategory::Unknown);
+        assert_eq!(report.exit_code(), 1);
+    }
+
+    #[test]
+    fn serializes_report_as_json() {
+        let report = ErrorReport::new(&GithubError::MissingToken);
+        let value: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+
+        assert_eq!(value, json!({
+            "kind": "missing_token",
+            "category": "authentication",
+            "exit_code": 7,
+            "message": GithubError::MissingToken.to_string(),
+            "causes": [],
+        }))
+    }
+
+    #[test]
+    fn maps_refused_connection() {
+        let error = reqwest::blocking::get(format!("http://{}", unused_address())).unwrap_err();
+        assert!(matches!(GithubError::from(error), GithubError::Connection(_)))
+    }
+
+    #[test]
+    fn maps_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || listener.accept().map(|(stream, _)| {
+            thread::sleep(Duration::from_millis(500));
+            drop(stream)
+        }));
+
+        let client = Client::builder().timeout(Duration::from_millis(50)).build().unwrap();
+        let error = client.get(format!("http://{address}")).send().unwrap_err();
+        handle.join().unwrap().unwrap();
+
+        assert!(matches!(GithubError::from(error), GithubError::Timeout(_)))
+    }
+
+    #[test]
+    fn maps_tls_failure() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || listener.accept().map(|(stream, _)| drop(stream)));
+
+        let error = reqwest::blocking::get(format!("https://{address}")).unwrap_err();
+        handle.join().unwrap().unwrap();
+
+        assert!(matches!(GithubError::from(error), GithubError::Tls(_)))
+    }
 }
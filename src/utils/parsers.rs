@@ -1,4 +1,7 @@
+use std::ops::RangeInclusive;
 use regex::Regex;
+use chrono::NaiveDate;
+use crate::{GlyphRamp, Palette, PALETTE_PRESETS};
 
 fn validate_regex(value: &str, reg_exp: &Regex) -> Result<String, String> {
     match reg_exp.is_match(value) {
@@ -25,20 +28,165 @@ pub fn parse_slug(value: &str) -> Result<String, String> {
     Ok(result)
 }
 
-/// Attempts to parse a provided Year argument, based on simple regular
-/// expression. Assures that only valid calendar years are being provided
-/// as an argument to clap.
+/// Attempts to parse a list of Github profile slugs, one per line, such as the
+/// contents of a team file. Blank lines and lines starting with `#` are ignored.
 ///
 /// # Errors
-/// Returns an error if provided year argument does not match regular
-/// expression.
+/// Returns an error naming the line of the first invalid slug.
+///
+pub fn parse_slug_list(value: &str) -> Result<Vec<String>, String> {
+    value
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| parse_slug(line).map_err(|e| format!("line {number}: {e}")))
+        .collect()
+}
+
+/// Attempts to parse a provided Year argument, as either a single calendar
+/// year (`2022`), or an inclusive range of years (`2019..2024`). Lists of
+/// years are split on commas by Clap before being parsed.
 ///
-pub fn parse_year(value: &str) -> Result<String, String> {
+/// # Errors
+/// Returns an error if any year is not a four digit calendar year, or if a
+/// range ends before it starts.
+///
+pub fn parse_year(value: &str) -> Result<RangeInclusive<i32>, String> {
     let year_regex = Regex::new(r"^[\d]{4}$").unwrap();
+    let parse = |year: &str| validate_regex(year, &year_regex)
+        .map(|year| year.parse::<i32>().unwrap())
+        .map_err(|_| String::from("year must be a valid calendar year, e.g. 2022, a range, e.g. 2019..2024, or a list, e.g. 2019,2021"));
+
+    match value.split_once("..") {
+        Some((start, end)) => {
+            let (start, end) = (parse(start)?, parse(end)?);
+
+            match start > end {
+                true => Err(format!("year range {start}..{end} must not end before it starts")),
+                false => Ok(start..=end),
+            }
+        },
+        None => parse(value).map(|year| year..=year),
+    }
+}
+
+/// Attempts to parse a calendar date, formatted as `YYYY-MM-DD`.
+///
+/// # Errors
+/// Returns an error if provided date argument is not a valid calendar date.
+///
+pub fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| String::from("date must be a valid calendar date, e.g. 2023-04-01"))
+}
 
-    let result = validate_regex(value, &year_regex).map_err(|_|
-        "year must be a valid calendar year, e.g. 2022"
+/// Attempts to parse a hexadecimal RGB color, e.g. `#216e39`. The leading
+/// hash is optional.
+///
+/// # Errors
+/// Returns an error if provided color argument is not a six digit
+/// hexadecimal color.
+///
+pub fn parse_hex_color(value: &str) -> Result<(u8, u8, u8), String> {
+    let hex_color_regex = Regex::new(r"^#?[0-9a-fA-F]{6}$").unwrap();
+
+    let hex = validate_regex(value.trim(), &hex_color_regex).map_err(|_|
+        format!("'{value}' must be a hexadecimal color, e.g. #216e39")
     )?;
-    
-    Ok(result)
+
+    let hex = hex.trim_start_matches('#');
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).unwrap();
+
+    Ok((channel(0), channel(2), channel(4)))
+}
+
+/// Attempts to parse a heatmap palette, either as the name of a preset
+/// palette, or as a comma-separated list of five hexadecimal colors.
+///
+/// # Errors
+/// Returns an error if provided palette argument is neither a known preset,
+/// nor a valid list of hexadecimal colors.
+///
+pub fn parse_palette(value: &str) -> Result<Palette, String> {
+    if let Some(palette) = Palette::preset(value) {
+        return Ok(palette);
+    }
+
+    Palette::from_hex_list(value).map_err(|e| format!(
+        "{e}. Expected a preset ({}) or five comma-separated hex colors",
+        PALETTE_PRESETS.join(", ")
+    ))
+}
+
+/// Attempts to parse a glyph ramp, ordered from least to most contributions.
+///
+/// # Errors
+/// Returns an error if provided ramp contains fewer than two glyphs.
+///
+pub fn parse_glyph_ramp(value: &str) -> Result<GlyphRamp, String> {
+    GlyphRamp::new(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hex_color() {
+        assert_eq!(parse_hex_color("#216e39"), Ok((0x21, 0x6e, 0x39)));
+        assert_eq!(parse_hex_color("EBEDF0"), Ok((0xeb, 0xed, 0xf0)));
+    }
+
+    #[test]
+    fn parses_palette_preset_or_list() {
+        assert_eq!(parse_palette("viridis"), Ok(Palette::preset("viridis").unwrap()));
+        assert_eq!(
+            parse_palette("#ebedf0,#9be9a8,#40c463,#30a14e,#216e39"),
+            Ok(Palette::preset("github-light").unwrap())
+        );
+        assert!(parse_palette("rainbow").is_err());
+    }
+
+    #[test]
+    fn parses_slug_list() {
+        assert_eq!(parse_slug_list("alice\n\n# on leave\n  bob  \n"), Ok(vec!["alice".to_string(), "bob".to_string()]));
+        assert_eq!(
+            parse_slug_list("alice\nbob smith"),
+            Err("line 2: slug must only contain alphanumeric characters and/or hyphens".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_years() {
+        assert_eq!(parse_year("2022"), Ok(2022..=2022));
+        assert_eq!(parse_year("2019..2022"), Ok(2019..=2022));
+    }
+
+    #[test]
+    fn error_if_invalid_years() {
+        assert!(parse_year("22").is_err());
+        assert!(parse_year("2019..").is_err());
+        assert!(parse_year("").is_err());
+        assert_eq!(parse_year("2024..2019"), Err("year range 2024..2019 must not end before it starts".to_string()));
+    }
+
+    #[test]
+    fn parses_date() {
+        assert_eq!(parse_date("2023-04-01"), Ok(NaiveDate::from_ymd_opt(2023, 4, 1).unwrap()));
+        assert!(parse_date("2023-02-30").is_err());
+        assert!(parse_date("01/04/2023").is_err());
+    }
+
+    #[test]
+    fn parses_glyph_ramp() {
+        assert_eq!(parse_glyph_ramp("·•●").unwrap().glyphs, vec!['·', '•', '●']);
+        assert!(parse_glyph_ramp("").is_err());
+    }
+
+    #[test]
+    fn error_if_invalid_hex_color() {
+        assert!(parse_hex_color("#21e39").is_err());
+        assert!(parse_hex_color("green").is_err());
+    }
 }